@@ -7,59 +7,142 @@ const WASM_PATH =
     ? 'wasm/perlin-wave/perlin-wave_bg.wasm'
     : void 0;
 
-const COLOR = '#c0e822';
-const COLOR_DARK = '#759203';
+// Clicking [Theme] goes through them.
+const THEMES = ['dark', 'light', 'high-contrast'];
 
 const APP_CONFIG = {
-  bgcolor: '#222',
+  // dark, light, high-contrast, or { background, primary, secondary,
+  // accent, font, line_width, glow } (what is left out is of dark)
+  theme: THEMES[0],
+  // The same seed draws the same waves. Random when null,
+  // and logged to the console for it to be given here.
+  seed: null,
+  // perlin, simplex, supersimplex, value, worley, or opensimplex
+  noise: 'opensimplex',
+  // Length of a cycle in milliseconds.
+  cycle: 350,
+  // Points sampled from the noise, and the width they are sampled over.
+  segments: 110,
+  normal_width: 550,
+  // Multiplies the points. The sliders on the control panel
+  // change it live, as well as cycle, segments, and the
+  // frequency and octaves of the fractal.
+  amplitude: 1.0,
+  // none, fbm, ridged, billow, or turbulence
+  fractal: {
+    type: 'none',
+    octaves: 6,
+    lacunarity: 2.0,
+    persistence: 0.5,
+    frequency: 1.0,
+  },
+  // Each panel is as wide as the element of the same `id`,
+  // and `ratio` (width over height) decides the height.
+  // Also: min_height, max_height, and fit (contain, cover, or
+  // fixed with width and height).
   panels: [
     {
       id: 'control',
-      ratio: 15.0 / 1.0,
-      color: COLOR,
-      color2: COLOR_DARK,
+      // Room for the readout and the sliders.
+      ratio: 10.0 / 1.0,
+      // value, min, max, mean, rms, crossings, peak,
+      // seed, noise, fps, or frame_time
+      metrics: ['value'],
+      // 'compact' (in a line) or 'expanded' (one per row)
+      readout: 'compact',
     },
     {
       id: 'wave',
       ratio: 3.0 / 1.0,
-      color: COLOR,
-      color2: COLOR_DARK,
+      // Shown first, and the order clicking goes through.
+      graph: 'radio',
+      rotation: ['radio', 'bars', 'solar', 'area', 'mirror', 'dots', 'stems'],
+      // Rotates by itself every N cycles (null for never).
+      rotate_every: null,
+      // 'none', 'fade', or 'morph' (radio <-> bars <-> solar)
+      transition: { type: 'fade', duration: 300, easing: 'sine' },
+      // 'pulse' or 'flow' (scrolls continuously)
+      mode: 'pulse',
+      flow: { speed: 10, evolution: 0.5 },
+      // linear, quad, cubic, quart, sine, expo, elastic,
+      // bounce, back, or 'cubic-bezier(x1, y1, x2, y2)'
+      easing: {
+        radio: 'quad',
+        bars: 'linear',
+        solar: 'linear',
+        area: 'quad',
+        mirror: 'quad',
+        dots: 'linear',
+        stems: 'linear',
+      },
+      // Radio, area, and mirror: linear, catmull-rom, monotone, or quadratic
+      curve: { type: 'linear', tension: 0 },
+      // palette: viridis, magma, plasma, inferno, or [[offset, color], ...]
+      // gradient: linear or radial, for the lines
+      colors: { palette: null, gradient: 'linear' },
     },
-  ].reduce(panelsReducer, []),
+  ],
 };
 
+let animation = null;
+
 document.addEventListener('DOMContentLoaded', () => {
   init(WASM_PATH)
     .then(() => {
-      PerlinWave.run(APP_CONFIG);
+      animation = PerlinWave.run(APP_CONFIG);
+      // The loop stops on an error while drawing.
+      animation.onError(err => {
+        console.error(`[${err.kind || 'unknown'}]`, err.message);
+      });
+      bindDownloadSvg('#download-svg', 'wave');
+      bindSwitchTheme('#switch-theme');
     })
     .catch(err => {
-      console.error(err);
+      // `kind` is one of: config, dom, context, borrow, render, or argument.
+      // For `config`, `errors` lists `{ path, message }` of each field.
+      console.error(`[${err.kind || 'unknown'}]`, err.message, err.errors || '');
     });
 });
 
 if (typeof module.hot !== 'undefined') {
   module.hot.accept();
+  // Otherwise, canvases pile up on every reload.
+  module.hot.dispose(() => {
+    if (animation) {
+      animation.stop();
+      animation.free();
+    }
+  });
 }
 
-function panelsReducer(acc = [], { id, ratio, color, color2 }) {
-  const key = `#${id}`;
+function bindDownloadSvg(key, id) {
   const el = document.querySelector(key);
   if (el) {
-    const width = (el.offsetWidth || 0).toFixed(1);
-    const height = (width / ratio).toFixed(1);
-    acc.push({
-      id,
-      color,
-      color2,
-      width,
-      height,
+    el.addEventListener('click', e => {
+      e.preventDefault();
+      if (!animation) return;
+      const svg = animation.exportSvg(id);
+      const url = URL.createObjectURL(
+        new Blob([svg], { type: 'image/svg+xml' })
+      );
+      const a = document.createElement('a');
+      a.href = url;
+      a.download = `perlin-${id}.svg`;
+      a.click();
+      URL.revokeObjectURL(url);
     });
+  }
+}
 
-    // The wrapper element for canvas produces
-    // a weird gap underneath the canvas,
-    // and we need to get rid of that.
-    el.style.height = `${height}px`;
+function bindSwitchTheme(key) {
+  const el = document.querySelector(key);
+  if (el) {
+    let index = 0;
+    el.addEventListener('click', e => {
+      e.preventDefault();
+      if (!animation) return;
+      index = (index + 1) % THEMES.length;
+      animation.setTheme(THEMES[index]);
+    });
   }
-  return acc;
 }
@@ -0,0 +1,136 @@
+use std::fmt;
+
+/// A color parsed from CSS-like strings such as
+/// `#222`, `#c0e822`, `#c0e82280`, `rgb(1, 2, 3)` or `rgba(1, 2, 3, 0.5)`.
+/// Backends which are not a browser need this to
+/// understand the colors given in `Config`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Rgba {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    /// Between `0.0` and `1.0`.
+    pub a: f64,
+}
+
+impl Rgba {
+    pub const BLACK: Rgba = Rgba {
+        r: 0,
+        g: 0,
+        b: 0,
+        a: 1.0,
+    };
+
+    /// Between `self` (`0.0`) and `other` (`1.0`).
+    pub fn mix(&self, other: &Rgba, t: f64) -> Rgba {
+        let channel = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * t).round() as u8;
+        Rgba {
+            r: channel(self.r, other.r),
+            g: channel(self.g, other.g),
+            b: channel(self.b, other.b),
+            a: self.a + (other.a - self.a) * t,
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Rgba> {
+        let s = s.trim();
+        if let Some(hex) = s.strip_prefix('#') {
+            parse_hex(hex)
+        } else if let Some(args) = s.strip_prefix("rgba(").or_else(|| s.strip_prefix("rgb(")) {
+            parse_rgb(args.strip_suffix(')')?)
+        } else {
+            None
+        }
+    }
+}
+
+/// `#rrggbb`, or `rgba(r, g, b, a)` when not opaque.
+impl fmt::Display for Rgba {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.a >= 1.0 {
+            write!(f, "#{:02x}{:02x}{:02x}", self.r, self.g, self.b)
+        } else {
+            write!(f, "rgba({}, {}, {}, {})", self.r, self.g, self.b, self.a)
+        }
+    }
+}
+
+fn parse_hex(hex: &str) -> Option<Rgba> {
+    let digit = |i: usize| u8::from_str_radix(hex.get(i..i + 1)?, 16).ok();
+    let byte = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+
+    match hex.len() {
+        3 | 4 => Some(Rgba {
+            r: digit(0)? * 17,
+            g: digit(1)? * 17,
+            b: digit(2)? * 17,
+            a: if hex.len() == 4 {
+                (digit(3)? * 17) as f64 / 255.0
+            } else {
+                1.0
+            },
+        }),
+        6 | 8 => Some(Rgba {
+            r: byte(0)?,
+            g: byte(2)?,
+            b: byte(4)?,
+            a: if hex.len() == 8 {
+                byte(6)? as f64 / 255.0
+            } else {
+                1.0
+            },
+        }),
+        _ => None,
+    }
+}
+
+fn parse_rgb(args: &str) -> Option<Rgba> {
+    let args: Vec<&str> = args.split(',').map(|v| v.trim()).collect();
+    let channel = |i: usize| -> Option<u8> {
+        let v: f64 = args.get(i)?.parse().ok()?;
+        Some(v.clamp(0.0, 255.0).round() as u8)
+    };
+
+    match args.len() {
+        3 | 4 => Some(Rgba {
+            r: channel(0)?,
+            g: channel(1)?,
+            b: channel(2)?,
+            a: if args.len() == 4 {
+                args[3].parse::<f64>().ok()?.clamp(0.0, 1.0)
+            } else {
+                1.0
+            },
+        }),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let rgb = |r, g, b, a| Some(Rgba { r, g, b, a });
+        assert_eq!(Rgba::parse("#222"), rgb(0x22, 0x22, 0x22, 1.0));
+        assert_eq!(Rgba::parse("#c0e822"), rgb(0xc0, 0xe8, 0x22, 1.0));
+        assert_eq!(Rgba::parse("#c0e82200"), rgb(0xc0, 0xe8, 0x22, 0.0));
+        assert_eq!(Rgba::parse("rgb(1, 2, 3)"), rgb(1, 2, 3, 1.0));
+        assert_eq!(Rgba::parse("rgba(1,2,3,0.5)"), rgb(1, 2, 3, 0.5));
+        assert_eq!(Rgba::parse("green"), None);
+        assert_eq!(Rgba::parse("#12345"), None);
+    }
+
+    #[test]
+    fn mix_and_print() {
+        let black = Rgba::BLACK;
+        let white = Rgba::parse("#fff").unwrap();
+        assert_eq!(black.mix(&white, 0.5).to_string(), "#808080");
+        let clear = Rgba { a: 0.0, ..white };
+        assert_eq!(
+            white.mix(&clear, 0.5).to_string(),
+            "rgba(255, 255, 255, 0.5)"
+        );
+    }
+}
@@ -0,0 +1,869 @@
+//! What JS passes to `run`.
+//! Deserialized with serde and then validated so that
+//! every invalid field is reported at once with its path.
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::Value;
+use serde_path_to_error::Segment;
+use std::collections::HashSet;
+use std::fmt;
+use std::mem;
+
+use crate::color::Rgba;
+use crate::constants::{CYCLE_MS, NORMAL_WIDTH, SEGMENTS};
+use crate::easing::EasingConfig;
+use crate::fractal::FractalConfig;
+use crate::layout::{FitMode, Layout};
+use crate::palette::ColorConfig;
+use crate::perlin::NoiseAlgorithm;
+use crate::spline::CurveConfig;
+use crate::stats::{Metric, ReadoutMode};
+use crate::theme::{Theme, ThemeSpec};
+use crate::transition::TransitionConfig;
+use crate::types::GraphType;
+use crate::wave::{FlowConfig, WaveMode};
+
+fn default_rotation() -> Vec<GraphType> {
+    GraphType::ALL.to_vec()
+}
+
+fn default_cycle() -> f64 {
+    CYCLE_MS
+}
+
+fn default_segments() -> usize {
+    SEGMENTS
+}
+
+fn default_normal_width() -> f64 {
+    NORMAL_WIDTH
+}
+
+fn default_metrics() -> Vec<Metric> {
+    vec![Metric::Value]
+}
+
+fn default_history() -> usize {
+    120
+}
+
+fn default_amplitude() -> f64 {
+    1.0
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// Either the name of a preset or the whole theme.
+    #[serde(default)]
+    pub theme: ThemeSpec,
+    /// Overrides `background` of the theme.
+    #[serde(default)]
+    pub bgcolor: Option<String>,
+    /// Seed for the noise and the offsets.
+    /// When omitted, a random seed up to `MAX_SEED` is used.
+    /// Larger ones need a `BigInt` from JS.
+    #[serde(default)]
+    pub seed: Option<u64>,
+    /// Which noise generator to use.
+    #[serde(default)]
+    pub noise: NoiseAlgorithm,
+    /// Octaves composed on top of the noise.
+    #[serde(default)]
+    pub fractal: FractalConfig,
+    /// Length of a cycle in milliseconds.
+    #[serde(default = "default_cycle")]
+    pub cycle: f64,
+    /// When given, every frame advances by this many
+    /// milliseconds regardless of the actual time spent.
+    #[serde(default)]
+    pub step: Option<f64>,
+    /// Number of points sampled from the noise.
+    #[serde(default = "default_segments")]
+    pub segments: usize,
+    /// Width over which the points are sampled.
+    /// The wider, the more ups and downs.
+    #[serde(default = "default_normal_width")]
+    pub normal_width: f64,
+    /// Multiplies the points, which are clamped to `-1.0..=1.0`.
+    #[serde(default = "default_amplitude")]
+    pub amplitude: f64,
+    pub panels: Vec<PanelSpec>,
+}
+
+/// Each panel is bound to the DOM element of the same `id`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "id", rename_all = "lowercase")]
+pub enum PanelSpec {
+    Wave(Box<WavePanelSpec>),
+    Control(ControlPanelSpec),
+}
+
+impl PanelSpec {
+    pub fn id(&self) -> &str {
+        match self {
+            PanelSpec::Wave(_) => "wave",
+            PanelSpec::Control(_) => "control",
+        }
+    }
+
+    pub fn layout(&self) -> Layout {
+        match self {
+            PanelSpec::Wave(p) => Layout {
+                width: p.width,
+                height: p.height,
+                ratio: p.ratio,
+                min_height: p.min_height,
+                max_height: p.max_height,
+                fit: p.fit,
+            },
+            PanelSpec::Control(p) => Layout {
+                width: p.width,
+                height: p.height,
+                ratio: p.ratio,
+                min_height: p.min_height,
+                max_height: p.max_height,
+                fit: p.fit,
+            },
+        }
+    }
+
+    /// `theme` with the colors given to the panel.
+    pub fn theme(&self, theme: &Theme) -> Theme {
+        let mut theme = theme.clone();
+        match self {
+            PanelSpec::Wave(p) => {
+                if let Some(color) = &p.color2 {
+                    theme.primary = color.clone();
+                }
+            }
+            PanelSpec::Control(p) => {
+                if let Some(color) = &p.color {
+                    theme.secondary = color.clone();
+                }
+                if let Some(color) = &p.color2 {
+                    theme.accent = color.clone();
+                }
+            }
+        }
+        theme
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct WavePanelSpec {
+    /// In CSS pixels. Given as they are for `FitMode::Fixed`,
+    /// and for the ratio when `ratio` is not.
+    #[serde(default)]
+    pub width: Option<f64>,
+    #[serde(default)]
+    pub height: Option<f64>,
+    /// Width over height.
+    #[serde(default)]
+    pub ratio: Option<f64>,
+    #[serde(default)]
+    pub min_height: Option<f64>,
+    #[serde(default)]
+    pub max_height: Option<f64>,
+    /// How the panel fits in its wrapper element.
+    #[serde(default)]
+    pub fit: FitMode,
+    /// Without `colors.palette`, the color at the peaks,
+    /// blended into from `color2` as the value grows.
+    #[serde(default)]
+    pub color: Option<String>,
+    /// Overrides `primary` of the theme.
+    #[serde(default)]
+    pub color2: Option<String>,
+    /// Shown first.
+    #[serde(default)]
+    pub graph: GraphType,
+    /// Clicking the panel goes through them in this order.
+    #[serde(default = "default_rotation")]
+    pub rotation: Vec<GraphType>,
+    /// Goes to the next in `rotation` every this many cycles.
+    #[serde(default)]
+    pub rotate_every: Option<u32>,
+    /// When the graph type changes.
+    #[serde(default)]
+    pub transition: TransitionConfig,
+    #[serde(default)]
+    pub mode: WaveMode,
+    /// Only for `WaveMode::Flow`.
+    #[serde(default)]
+    pub flow: FlowConfig,
+    /// For each `GraphType`.
+    #[serde(default)]
+    pub easing: EasingConfig,
+    /// For the lines of `GraphType::Radio`, `Area`, and `Mirror`.
+    #[serde(default)]
+    pub curve: CurveConfig,
+    /// Colors by value instead of in `color` alone.
+    #[serde(default)]
+    pub colors: ColorConfig,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct ControlPanelSpec {
+    /// The same as `WavePanelSpec`.
+    #[serde(default)]
+    pub width: Option<f64>,
+    #[serde(default)]
+    pub height: Option<f64>,
+    /// Width over height.
+    #[serde(default)]
+    pub ratio: Option<f64>,
+    #[serde(default)]
+    pub min_height: Option<f64>,
+    #[serde(default)]
+    pub max_height: Option<f64>,
+    /// How the panel fits in its wrapper element.
+    #[serde(default)]
+    pub fit: FitMode,
+    /// Overrides `secondary` of the theme, for the text.
+    #[serde(default)]
+    pub color: Option<String>,
+    /// Overrides `accent` of the theme, for the tip of the sparkline
+    /// and the slider being dragged.
+    #[serde(default)]
+    pub color2: Option<String>,
+    /// Shown in the readout, in the order given.
+    #[serde(default = "default_metrics")]
+    pub metrics: Vec<Metric>,
+    #[serde(default)]
+    pub readout: ReadoutMode,
+    /// Number of cycles kept for the sparkline next to the readout,
+    /// however long a cycle is. No sparkline when `0`.
+    #[serde(default = "default_history")]
+    pub history: usize,
+}
+
+/// One invalid field. `path` looks like `panels[1].width`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FieldError {
+    pub path: String,
+    pub message: String,
+}
+
+/// Every invalid field found in `Config`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConfigError {
+    pub errors: Vec<FieldError>,
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid config:")?;
+        for e in &self.errors {
+            write!(f, "\n  {}: {}", e.path, e.message)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+/// Collects `FieldError` while validating.
+#[derive(Default)]
+struct Errors(Vec<FieldError>);
+
+impl Errors {
+    fn push(&mut self, path: &str, message: &str) {
+        self.0.push(FieldError {
+            path: path.into(),
+            message: message.into(),
+        });
+    }
+
+    fn positive(&mut self, path: &str, value: f64) {
+        if !(value.is_finite() && value > 0.0) {
+            self.push(path, &format!("must be a positive number, got {}", value));
+        }
+    }
+
+    /// Canvas understands far more than `Rgba` does,
+    /// so named colors (`green`) pass as they are.
+    fn color(&mut self, path: &str, value: &str) {
+        let named = !value.is_empty() && value.chars().all(|c| c.is_ascii_alphabetic());
+        if !named && Rgba::parse(value).is_none() {
+            self.push(path, &format!("invalid color: {:?}", value));
+        }
+    }
+
+    fn layout(&mut self, path: &str, layout: &Layout) {
+        let path = |key: &str| format!("{}{}", path, key);
+        let sizes = [
+            ("width", layout.width),
+            ("height", layout.height),
+            ("ratio", layout.ratio),
+            ("min_height", layout.min_height),
+            ("max_height", layout.max_height),
+        ];
+        for (key, value) in sizes.iter() {
+            if let Some(value) = value {
+                self.positive(&path(key), *value);
+            }
+        }
+        if let (Some(min), Some(max)) = (layout.min_height, layout.max_height) {
+            if min > max {
+                self.push(&path("max_height"), "must not be less than min_height");
+            }
+        }
+        match layout.fit {
+            FitMode::Fixed => {
+                if layout.width.is_none() || layout.height.is_none() {
+                    self.push(&path("fit"), "fixed needs both width and height");
+                }
+            }
+            _ => {
+                if layout.ratio().is_none() {
+                    self.push(&path("ratio"), "needed unless width and height are given");
+                }
+            }
+        }
+    }
+
+    /// Deserializes `value` as `T`, dropping each field serde rejects.
+    /// `None` when it is still rejected without them.
+    fn deserialize<T: DeserializeOwned>(&mut self, prefix: &str, mut value: Value) -> Option<T> {
+        loop {
+            let e = match serde_path_to_error::deserialize(&value) {
+                Ok(v) => return Some(v),
+                Err(e) => e,
+            };
+            let path = match (prefix, e.path().iter().next()) {
+                (prefix, None) => prefix.to_string(),
+                ("", Some(_)) => e.path().to_string(),
+                (prefix, Some(_)) => format!("{}.{}", prefix, e.path()),
+            };
+            self.push(&path, &e.inner().to_string());
+            // The first key is the field of `T` to drop.
+            let key = match e.path().iter().next() {
+                Some(Segment::Map { key }) => key.clone(),
+                _ => return None,
+            };
+            value.as_object_mut()?.remove(&key)?;
+        }
+    }
+
+    /// Deserializes the `i`th of `panels` by its `id`.
+    fn panel(&mut self, i: usize, mut value: Value) -> Option<PanelSpec> {
+        let prefix = format!("panels[{}]", i);
+        let id = match value.as_object_mut() {
+            Some(object) => object.remove("id"),
+            None => {
+                self.push(&prefix, "must be an object");
+                return None;
+            }
+        };
+        match id.as_ref().and_then(Value::as_str) {
+            Some("wave") => self
+                .deserialize(&prefix, value)
+                .map(|p| PanelSpec::Wave(Box::new(p))),
+            Some("control") => self.deserialize(&prefix, value).map(PanelSpec::Control),
+            Some(id) => {
+                let path = format!("{}.id", prefix);
+                self.push(&path, &format!("no such panel: {}", id));
+                None
+            }
+            None => {
+                let path = format!("{}.id", prefix);
+                self.push(&path, "must be either wave or control");
+                None
+            }
+        }
+    }
+
+    /// `ids` are of the panels checked so far.
+    fn panel_spec<'a>(&mut self, i: usize, panel: &'a PanelSpec, ids: &mut HashSet<&'a str>) {
+        let path = |key: &str| format!("panels[{}].{}", i, key);
+
+        if !ids.insert(panel.id()) {
+            self.push(&path("id"), &format!("duplicate panel: {}", panel.id()));
+        }
+
+        let (color, color2) = match panel {
+            PanelSpec::Wave(p) => (&p.color, &p.color2),
+            PanelSpec::Control(p) => (&p.color, &p.color2),
+        };
+        self.layout(&path(""), &panel.layout());
+        if let Some(color) = color {
+            match panel {
+                // Mixed with `color2` without the browser.
+                PanelSpec::Wave(_) if Rgba::parse(color).is_none() => self.push(
+                    &path("color"),
+                    &format!("invalid color (hex or rgb only): {:?}", color),
+                ),
+                _ => self.color(&path("color"), color),
+            }
+        }
+        if let Some(color2) = color2 {
+            self.color(&path("color2"), color2);
+        }
+
+        if let PanelSpec::Wave(p) = panel {
+            if !(p.flow.speed.is_finite() && p.flow.speed >= 0.0) {
+                self.push(&path("flow.speed"), "must be zero or positive");
+            }
+            if !(p.flow.evolution.is_finite() && p.flow.evolution >= 0.0) {
+                self.push(&path("flow.evolution"), "must be zero or positive");
+            }
+            if p.rotation.is_empty() {
+                self.push(&path("rotation"), "must not be empty");
+            } else if !p.rotation.contains(&p.graph) {
+                self.push(&path("graph"), &format!("{:?} is not in rotation", p.graph));
+            }
+            let mut seen = HashSet::new();
+            if p.rotation.iter().any(|g| !seen.insert(*g)) {
+                self.push(&path("rotation"), "must not have duplicates");
+            }
+            if p.rotate_every == Some(0) {
+                self.push(&path("rotate_every"), "must be positive");
+            }
+            if !(p.transition.duration.is_finite() && p.transition.duration >= 0.0) {
+                self.push(&path("transition.duration"), "must not be negative");
+            }
+            if !(0.0..=1.0).contains(&p.curve.tension) {
+                self.push(&path("curve.tension"), "must be between 0 and 1");
+            }
+            if let Some(Err(message)) = p.colors.palette.as_ref().map(|p| p.validate()) {
+                self.push(&path("colors.palette"), &message);
+            }
+        }
+
+        if let PanelSpec::Control(p) = panel {
+            if p.history > 10_000 {
+                self.push(&path("history"), "must be between 0 and 10000");
+            }
+        }
+    }
+
+    /// Only the theme given as a whole, since presets are valid.
+    fn theme(&mut self, path: &str, spec: &ThemeSpec) {
+        let theme = match spec {
+            ThemeSpec::Named(_) => return,
+            ThemeSpec::Custom(theme) => theme,
+        };
+        let path = |key: &str| format!("{}{}", path, key);
+        self.color(&path("background"), &theme.background);
+        self.color(&path("primary"), &theme.primary);
+        self.color(&path("secondary"), &theme.secondary);
+        self.color(&path("accent"), &theme.accent);
+        self.positive(&path("line_width"), theme.line_width);
+        if !(theme.glow.is_finite() && theme.glow >= 0.0) {
+            self.push(&path("glow"), "must be zero or positive");
+        }
+    }
+}
+
+impl Config {
+    /// Deserializes and validates.
+    /// A field serde rejects is reported and left to its default
+    /// so that the rest of the config is still validated.
+    pub fn from_deserializer<'de, D>(deserializer: D) -> Result<Config, ConfigError>
+    where
+        D: Deserializer<'de>,
+    {
+        let mut value = Value::deserialize(deserializer).map_err(|e| ConfigError {
+            errors: vec![FieldError {
+                path: "".into(),
+                message: e.to_string(),
+            }],
+        })?;
+        let mut errors = Errors::default();
+
+        // Taken out since serde buffers internally tagged enums,
+        // and tells no path within them.
+        let panels = match value.get_mut("panels") {
+            Some(Value::Array(panels)) => mem::take(panels),
+            _ => vec![],
+        };
+        let config: Option<Config> = errors.deserialize("", value);
+        let panels: Vec<Option<PanelSpec>> = panels
+            .into_iter()
+            .enumerate()
+            .map(|(i, value)| errors.panel(i, value))
+            .collect();
+
+        let mut config = match config {
+            Some(config) => config,
+            None => return Err(ConfigError { errors: errors.0 }),
+        };
+        config.check(&mut errors);
+        let mut ids = HashSet::new();
+        for (i, panel) in panels.iter().enumerate() {
+            if let Some(panel) = panel {
+                errors.panel_spec(i, panel, &mut ids);
+            }
+        }
+
+        if errors.0.is_empty() {
+            config.panels = panels.into_iter().flatten().collect();
+            Ok(config)
+        } else {
+            Err(ConfigError { errors: errors.0 })
+        }
+    }
+
+    /// `theme` with `bgcolor`.
+    pub fn theme(&self) -> Theme {
+        let mut theme = self.theme.theme();
+        if let Some(bgcolor) = &self.bgcolor {
+            theme.background = bgcolor.clone();
+        }
+        theme
+    }
+
+    /// Of the wave panel when it is in `WaveMode::Flow`,
+    /// for the readout to describe what is drawn.
+    pub fn flow(&self) -> Option<&FlowConfig> {
+        self.panels.iter().find_map(|panel| match panel {
+            PanelSpec::Wave(p) if p.mode == WaveMode::Flow => Some(&p.flow),
+            _ => None,
+        })
+    }
+
+    /// `theme` in place of `self.theme` for the panel `id`,
+    /// with `bgcolor` and the colors given to the panel.
+    pub fn panel_theme(&self, id: &str, theme: &Theme) -> Theme {
+        let mut theme = theme.clone();
+        if let Some(bgcolor) = &self.bgcolor {
+            theme.background = bgcolor.clone();
+        }
+        match self.panels.iter().find(|panel| panel.id() == id) {
+            Some(panel) => panel.theme(&theme),
+            None => theme,
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let mut errors = Errors::default();
+        self.check(&mut errors);
+        let mut ids = HashSet::new();
+        for (i, panel) in self.panels.iter().enumerate() {
+            errors.panel_spec(i, panel, &mut ids);
+        }
+
+        if errors.0.is_empty() {
+            Ok(())
+        } else {
+            Err(ConfigError { errors: errors.0 })
+        }
+    }
+
+    /// All but the panels.
+    fn check(&self, errors: &mut Errors) {
+        errors.theme("theme.", &self.theme);
+        if let Some(bgcolor) = &self.bgcolor {
+            errors.color("bgcolor", bgcolor);
+        }
+        errors.positive("cycle", self.cycle);
+        if let Some(step) = self.step {
+            errors.positive("step", step);
+        }
+        if self.segments < 2 || self.segments > 4096 {
+            errors.push("segments", "must be between 2 and 4096");
+        }
+        errors.positive("normal_width", self.normal_width);
+        errors.positive("amplitude", self.amplitude);
+
+        let fractal = &self.fractal;
+        if fractal.octaves == 0 || fractal.octaves > 16 {
+            errors.push("fractal.octaves", "must be between 1 and 16");
+        }
+        errors.positive("fractal.lacunarity", fractal.lacunarity);
+        errors.positive("fractal.frequency", fractal.frequency);
+        if !(fractal.persistence.is_finite() && fractal.persistence >= 0.0) {
+            errors.push("fractal.persistence", "must be zero or positive");
+        }
+    }
+}
+
+impl ThemeSpec {
+    /// Deserializes and validates, as `Config` does.
+    pub fn from_deserializer<'de, D>(deserializer: D) -> Result<Theme, ConfigError>
+    where
+        D: Deserializer<'de>,
+    {
+        let spec: ThemeSpec = ThemeSpec::deserialize(deserializer).map_err(|e| ConfigError {
+            errors: vec![FieldError {
+                path: "theme".into(),
+                message: e.to_string(),
+            }],
+        })?;
+        let mut errors = Errors::default();
+        errors.theme("theme.", &spec);
+        if errors.0.is_empty() {
+            Ok(spec.theme())
+        } else {
+            Err(ConfigError { errors: errors.0 })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::easing::Easing;
+
+    fn parse(json: &str) -> Result<Config, ConfigError> {
+        Config::from_deserializer(&mut serde_json::Deserializer::from_str(json))
+    }
+
+    #[test]
+    fn defaults() {
+        let config =
+            parse(r#"{ "panels": [{ "id": "wave", "width": 300, "height": 100 }] }"#).unwrap();
+        assert_eq!(config.theme(), Theme::dark());
+        assert_eq!(config.theme().background, "#222");
+        assert_eq!(
+            config.panels,
+            vec![PanelSpec::Wave(Box::new(WavePanelSpec {
+                width: Some(300.0),
+                height: Some(100.0),
+                ratio: None,
+                min_height: None,
+                max_height: None,
+                fit: FitMode::Contain,
+                color: None,
+                color2: None,
+                graph: GraphType::Radio,
+                rotation: GraphType::ALL.to_vec(),
+                rotate_every: None,
+                transition: TransitionConfig::default(),
+                mode: WaveMode::Pulse,
+                flow: FlowConfig::default(),
+                easing: EasingConfig::default(),
+                curve: CurveConfig::default(),
+                colors: ColorConfig::default(),
+            }))]
+        );
+    }
+
+    #[test]
+    fn metrics_of_the_control_panel() {
+        let spec = |json: &str| match &parse(json).unwrap().panels[0] {
+            PanelSpec::Control(p) => p.clone(),
+            _ => unreachable!(),
+        };
+        let expanded = spec(
+            r#"{ "panels": [{ "id": "control", "ratio": 4,
+                "metrics": ["rms", "frame_time"], "readout": "expanded" }] }"#,
+        );
+        assert_eq!(expanded.metrics, vec![Metric::Rms, Metric::FrameTime]);
+        assert_eq!(expanded.readout, ReadoutMode::Expanded);
+        let compact = spec(r#"{ "panels": [{ "id": "control", "ratio": 15 }] }"#);
+        assert_eq!(compact.metrics, vec![Metric::Value]);
+        assert_eq!(compact.readout, ReadoutMode::Compact);
+        assert_eq!(compact.history, 120);
+        assert!(parse(r#"{ "panels": [{ "id": "control", "metrics": ["median"] }] }"#).is_err());
+        let err = parse(r#"{ "panels": [{ "id": "control", "ratio": 15, "history": 100000 }] }"#)
+            .unwrap_err();
+        assert_eq!(err.errors[0].path, "panels[0].history");
+        assert_eq!(err.errors[0].message, "must be between 0 and 10000");
+    }
+
+    #[test]
+    fn colors_override_the_theme() {
+        let config = parse(
+            r##"{ "theme": "light", "bgcolor": "#fff", "panels": [
+                { "id": "wave", "width": 300, "height": 100, "color2": "red" },
+                { "id": "control", "width": 300, "height": 20, "color2": "blue" }
+            ] }"##,
+        )
+        .unwrap();
+        let theme = config.theme();
+        assert_eq!(theme.background, "#fff");
+        assert_eq!(theme.line_width, Theme::light().line_width);
+        assert_eq!(config.panels[0].theme(&theme).primary, "red");
+        assert_eq!(config.panels[0].theme(&theme).accent, theme.accent);
+        assert_eq!(
+            config.panels[1].theme(&theme).secondary,
+            Theme::light().secondary
+        );
+        assert_eq!(config.panels[1].theme(&theme).accent, "blue");
+    }
+
+    #[test]
+    fn flow_of_the_wave_panel() {
+        let config = parse(
+            r#"{ "panels": [
+                { "id": "control", "ratio": 15 },
+                { "id": "wave", "ratio": 3, "mode": "flow", "flow": { "speed": 2 } }
+            ] }"#,
+        )
+        .unwrap();
+        assert_eq!(config.flow().map(|flow| flow.speed), Some(2.0));
+        let pulse = parse(r#"{ "panels": [{ "id": "wave", "ratio": 3 }] }"#).unwrap();
+        assert_eq!(pulse.flow(), None);
+    }
+
+    #[test]
+    fn colors_survive_a_theme_switch() {
+        let config = parse(
+            r##"{ "bgcolor": "#fff", "panels": [
+                { "id": "control", "width": 300, "height": 20, "color": "red" }
+            ] }"##,
+        )
+        .unwrap();
+        let theme = config.panel_theme("control", &Theme::high_contrast());
+        assert_eq!(theme.secondary, "red");
+        assert_eq!(theme.background, "#fff");
+        assert_eq!(theme.primary, Theme::high_contrast().primary);
+        assert_eq!(
+            config.panel_theme("wave", &Theme::dark()).primary,
+            Theme::dark().primary
+        );
+    }
+
+    #[test]
+    fn layout_of_each_panel() {
+        let config = parse(
+            r#"{ "panels": [
+                { "id": "wave", "ratio": 3, "max_height": 150 },
+                { "id": "control", "width": 300, "height": 20, "fit": "fixed" }
+            ] }"#,
+        )
+        .unwrap();
+        assert_eq!(config.panels[0].layout().size(600.0, 0.0), (600.0, 150.0));
+        assert_eq!(config.panels[1].layout().size(600.0, 0.0), (300.0, 20.0));
+
+        let err = parse(
+            r#"{ "panels": [
+                { "id": "wave", "min_height": 100, "max_height": 50 },
+                { "id": "control", "ratio": 15, "fit": "fixed" }
+            ] }"#,
+        )
+        .unwrap_err();
+        let paths: Vec<&str> = err.errors.iter().map(|e| e.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            vec!["panels[0].max_height", "panels[0].ratio", "panels[1].fit"]
+        );
+    }
+
+    #[test]
+    fn theme_from_json() {
+        let theme = ThemeSpec::from_deserializer(&mut serde_json::Deserializer::from_str(
+            r##"{ "accent": "#0ff", "glow": 3 }"##,
+        ))
+        .unwrap();
+        assert_eq!((theme.accent.as_str(), theme.glow), ("#0ff", 3.0));
+
+        let err = ThemeSpec::from_deserializer(&mut serde_json::Deserializer::from_str(
+            r##"{ "primary": "#12", "line_width": 0 }"##,
+        ))
+        .unwrap_err();
+        let paths: Vec<&str> = err.errors.iter().map(|e| e.path.as_str()).collect();
+        assert_eq!(paths, vec!["theme.primary", "theme.line_width"]);
+    }
+
+    #[test]
+    fn typo_in_a_key() {
+        let err = parse(
+            r#"{ "panels": [{ "id": "wave", "width": 300, "height": 100, "colour": "red" }] }"#,
+        )
+        .unwrap_err();
+        assert_eq!(err.errors.len(), 1);
+        assert_eq!(err.errors[0].path, "panels[0].colour");
+        assert!(err.errors[0].message.contains("colour"));
+    }
+
+    #[test]
+    fn no_such_panel() {
+        let err =
+            parse(r#"{ "panels": [{ "id": "clock", "width": 1, "height": 1 }] }"#).unwrap_err();
+        assert_eq!(err.errors[0].path, "panels[0].id");
+        assert!(err.errors[0].message.contains("clock"));
+    }
+
+    #[test]
+    fn easing_per_graph_type() {
+        let config = parse(
+            r#"{ "panels": [{ "id": "wave", "width": 300, "height": 100,
+                 "easing": { "bars": "bounce", "solar": "cubic-bezier(0.4, 0, 0.2, 1)" } }] }"#,
+        )
+        .unwrap();
+        match &config.panels[0] {
+            PanelSpec::Wave(spec) => {
+                assert_eq!(spec.easing.radio, Easing::Quad);
+                assert_eq!(spec.easing.bars, Easing::Bounce);
+                assert_eq!(spec.easing.solar, Easing::CubicBezier(0.4, 0.0, 0.2, 1.0));
+            }
+            _ => unreachable!(),
+        }
+
+        let err = parse(
+            r#"{ "panels": [{ "id": "wave", "width": 300, "height": 100,
+                 "easing": { "bars": "wobble" } }] }"#,
+        )
+        .unwrap_err();
+        assert_eq!(err.errors[0].path, "panels[0].easing.bars");
+        assert!(err.errors[0].message.contains("wobble"));
+    }
+
+    #[test]
+    fn every_invalid_field() {
+        let err = parse(
+            r##"{
+                "bgcolor": "#12",
+                "fractal": { "octaves": 0 },
+                "panels": [
+                    { "id": "wave", "width": 0, "height": 100, "curve": { "tension": 2 },
+                      "flow": { "speed": -10, "evolution": 0 },
+                      "graph": "dots", "rotation": ["bars", "bars"],
+                      "colors": { "palette": [[1, "#000"], [0, "#fff"]] } },
+                    { "id": "control", "width": 300, "height": -1, "color": "rgb(1, 2)" }
+                ]
+            }"##,
+        )
+        .unwrap_err();
+        let paths: Vec<&str> = err.errors.iter().map(|e| e.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                "bgcolor",
+                "fractal.octaves",
+                "panels[0].width",
+                "panels[0].flow.speed",
+                "panels[0].graph",
+                "panels[0].rotation",
+                "panels[0].curve.tension",
+                "panels[0].colors.palette",
+                "panels[1].height",
+                "panels[1].color",
+            ]
+        );
+    }
+
+    #[test]
+    fn serde_errors_along_with_the_others() {
+        let err = parse(
+            r##"{
+                "cycle": "fast",
+                "amplitude": 0,
+                "fractal": { "octave": 4 },
+                "panels": [
+                    { "id": "wave", "width": 300, "height": 100, "mode": "drift" },
+                    { "id": "control", "width": 0, "height": 20, "metrics": ["median"] }
+                ]
+            }"##,
+        )
+        .unwrap_err();
+        let paths: Vec<&str> = err.errors.iter().map(|e| e.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                "cycle",
+                "fractal.octave",
+                "panels[0].mode",
+                "panels[1].metrics[0]",
+                "amplitude",
+                "panels[1].width",
+            ]
+        );
+    }
+}
@@ -1,4 +1,8 @@
-pub const FULL_CYCLE: f64 = 20.0;
+/// Length of a cycle in milliseconds.
+pub const CYCLE_MS: f64 = 350.0;
 pub const SEGMENTS: usize = 110;
 pub const NORMAL_WIDTH: f64 = 550.0;
+/// Largest random seed, which is `Number.MAX_SAFE_INTEGER`
+/// so that JS can pass it back as a number.
+pub const MAX_SEED: u64 = (1 << 53) - 1;
 pub const FONT_FAMILY: &str = "Work Sans, -apple-system, BlinkMacSystemFont, Segoe UI, Roboto, Oxygen, Ubuntu, Cantarell, Fira Sans, Droid Sans, Helvetica Neue, sans-serif";
@@ -0,0 +1,251 @@
+//! Sliders drawn on the control panel
+//! to tune the wave while it is running.
+
+use crate::config::Config;
+use crate::geometry::Rect;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Param {
+    /// Multiplies the points.
+    Amplitude,
+    /// `fractal.frequency`.
+    Frequency,
+    /// `fractal.octaves`.
+    Octaves,
+    /// `cycle` in milliseconds.
+    Cycle,
+    Segments,
+}
+
+impl Param {
+    pub const ALL: [Param; 5] = [
+        Param::Amplitude,
+        Param::Frequency,
+        Param::Octaves,
+        Param::Cycle,
+        Param::Segments,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Param::Amplitude => "amp",
+            Param::Frequency => "freq",
+            Param::Octaves => "oct",
+            Param::Cycle => "cycle",
+            Param::Segments => "seg",
+        }
+    }
+
+    /// Minimum, maximum, and the step snapped to.
+    fn range(self) -> (f64, f64, f64) {
+        match self {
+            Param::Amplitude => (0.1, 2.0, 0.05),
+            Param::Frequency => (0.1, 4.0, 0.1),
+            Param::Octaves => (1.0, 8.0, 1.0),
+            Param::Cycle => (100.0, 2000.0, 10.0),
+            Param::Segments => (8.0, 256.0, 1.0),
+        }
+    }
+
+    pub fn format(self, value: f64) -> String {
+        match self {
+            Param::Amplitude => format!("{:.2}", value),
+            Param::Frequency => format!("{:.1}", value),
+            _ => format!("{}", value.round()),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Slider {
+    pub param: Param,
+    pub value: f64,
+    min: f64,
+    max: f64,
+    step: f64,
+}
+
+impl Slider {
+    /// The range is widened when `value` is out of it.
+    pub fn new(param: Param, value: f64) -> Slider {
+        let (min, max, step) = param.range();
+        Slider {
+            param,
+            value,
+            min: min.min(value),
+            max: max.max(value),
+            step,
+        }
+    }
+
+    /// Where the knob is, between `0.0` and `1.0`.
+    pub fn ratio(&self) -> f64 {
+        (self.value - self.min) / (self.max - self.min)
+    }
+
+    /// Returns `true` when the value changed.
+    fn set_ratio(&mut self, ratio: f64) -> bool {
+        let value = self.min + ratio.clamp(0.0, 1.0) * (self.max - self.min);
+        let value = (value / self.step).round() * self.step;
+        let value = value.clamp(self.min, self.max);
+        if (value - self.value).abs() < 1e-9 {
+            return false;
+        }
+        self.value = value;
+        true
+    }
+}
+
+/// Where each part of a slider is drawn.
+pub struct SliderLayout {
+    /// The whole of the slider, which takes the pointer.
+    pub cell: Rect,
+    /// Baseline of the label.
+    pub text_y: f64,
+    pub track: Rect,
+    pub knob_radius: f64,
+    pub font_size: f64,
+}
+
+/// The sliders, and which one is dragged.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Controls {
+    pub sliders: Vec<Slider>,
+    active: Option<usize>,
+    /// Changed but not taken yet.
+    changed: Vec<Param>,
+}
+
+impl Controls {
+    pub fn new(config: &Config) -> Controls {
+        let value = |param| match param {
+            Param::Amplitude => config.amplitude,
+            Param::Frequency => config.fractal.frequency,
+            Param::Octaves => config.fractal.octaves as f64,
+            Param::Cycle => config.cycle,
+            Param::Segments => config.segments as f64,
+        };
+        Controls {
+            sliders: Param::ALL
+                .iter()
+                .map(|p| Slider::new(*p, value(*p)))
+                .collect(),
+            active: None,
+            changed: vec![],
+        }
+    }
+
+    /// Width taken by the readout on the left.
+    pub fn readout_width(width: f64, height: f64) -> f64 {
+        (height * 4.0).min(width * 0.3)
+    }
+
+    /// For the `i`th slider on the panel of the size.
+    pub fn layout(&self, i: usize, width: f64, height: f64) -> SliderLayout {
+        let left = Controls::readout_width(width, height);
+        let cell_width = (width - left) / self.sliders.len().max(1) as f64;
+        let pad = cell_width * 0.08;
+        let font_size = (height * 0.3).min(cell_width / 6.0).floor();
+        let thickness = (height * 0.08).max(2.0);
+        let x = left + i as f64 * cell_width;
+        SliderLayout {
+            cell: Rect {
+                x,
+                y: 0.0,
+                width: cell_width,
+                height,
+            },
+            text_y: height * 0.45,
+            track: Rect {
+                x: x + pad,
+                y: height * 0.72 - thickness / 2.0,
+                width: cell_width - pad * 2.0,
+                height: thickness,
+            },
+            knob_radius: thickness * 1.5,
+            font_size,
+        }
+    }
+
+    /// Which slider is at the point, if any.
+    pub fn hit(&self, x: f64, y: f64, width: f64, height: f64) -> Option<usize> {
+        (0..self.sliders.len()).find(|i| {
+            let cell = self.layout(*i, width, height).cell;
+            x >= cell.x && x < cell.x + cell.width && y >= cell.y && y < cell.y + cell.height
+        })
+    }
+
+    /// Starts dragging the slider at the point.
+    /// Returns `true` when there was one.
+    pub fn pointer_down(&mut self, x: f64, y: f64, width: f64, height: f64) -> bool {
+        self.active = self.hit(x, y, width, height);
+        self.pointer_move(x, width, height);
+        self.active.is_some()
+    }
+
+    pub fn pointer_move(&mut self, x: f64, width: f64, height: f64) {
+        let i = match self.active {
+            Some(i) => i,
+            None => return,
+        };
+        let track = self.layout(i, width, height).track;
+        let slider = &mut self.sliders[i];
+        if slider.set_ratio((x - track.x) / track.width) && !self.changed.contains(&slider.param) {
+            self.changed.push(slider.param);
+        }
+    }
+
+    pub fn pointer_up(&mut self) {
+        self.active = None;
+    }
+
+    pub fn active(&self) -> Option<usize> {
+        self.active
+    }
+
+    /// The values changed since the last time.
+    pub fn take_changes(&mut self) -> Vec<(Param, f64)> {
+        let sliders = &self.sliders;
+        self.changed
+            .drain(..)
+            .filter_map(|p| sliders.iter().find(|s| s.param == p))
+            .map(|s| (s.param, s.value))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn controls() -> Controls {
+        let config: Config = serde_json::from_str(r#"{ "panels": [] }"#).unwrap();
+        Controls::new(&config)
+    }
+
+    #[test]
+    fn drags_and_snaps() {
+        let mut c = controls();
+        let (width, height) = (600.0, 40.0);
+        assert_eq!(c.hit(10.0, 20.0, width, height), None);
+
+        let track = c.layout(3, width, height).track;
+        assert!(c.pointer_down(track.x + track.width / 2.0, 30.0, width, height));
+        assert_eq!(c.active(), Some(3));
+        c.pointer_move(track.x + track.width + 50.0, width, height);
+        c.pointer_up();
+        c.pointer_move(track.x, width, height);
+
+        assert_eq!(c.take_changes(), vec![(Param::Cycle, 2000.0)]);
+        assert!(c.take_changes().is_empty());
+        assert_eq!(c.sliders[3].ratio(), 1.0);
+    }
+
+    #[test]
+    fn widened_to_the_value() {
+        let slider = Slider::new(Param::Segments, 1024.0);
+        assert_eq!(slider.ratio(), 1.0);
+        assert_eq!(Param::Amplitude.format(1.0), "1.00");
+        assert_eq!(Param::Cycle.format(350.0), "350");
+    }
+}
@@ -0,0 +1,272 @@
+//! Curves for the transitions between `points_prev` and `points`.
+//! Written the same way as CSS, such as `cubic`,
+//! or `cubic-bezier(0.4, 0, 0.2, 1)`.
+
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+use std::f64::consts::PI;
+use std::fmt;
+use std::str::FromStr;
+
+/// All but `Linear` and `CubicBezier` ease both in and out.
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq)]
+#[serde(try_from = "String", into = "String")]
+pub enum Easing {
+    Linear,
+    Quad,
+    Cubic,
+    Quart,
+    Sine,
+    Expo,
+    Elastic,
+    Bounce,
+    Back,
+    /// Control points `(x1, y1)` and `(x2, y2)`
+    /// of which `x1` and `x2` are between `0.0` and `1.0`.
+    CubicBezier(f64, f64, f64, f64),
+}
+
+impl Easing {
+    /// Maps `t` between `0.0` and `1.0` on the curve.
+    /// `Elastic` and `Back` go beyond the range on the way.
+    pub fn apply(&self, t: f64) -> f64 {
+        let t = t.clamp(0.0, 1.0);
+        match *self {
+            Easing::Linear => t,
+            Easing::Quad => in_out(t, |t| t * t),
+            Easing::Cubic => in_out(t, |t| t.powi(3)),
+            Easing::Quart => in_out(t, |t| t.powi(4)),
+            Easing::Sine => -((PI * t).cos() - 1.0) / 2.0,
+            Easing::Expo => in_out(t, |t| {
+                if t == 0.0 {
+                    0.0
+                } else {
+                    2_f64.powf(10.0 * t - 10.0)
+                }
+            }),
+            Easing::Elastic => in_out(t, |t| {
+                if t == 0.0 || t == 1.0 {
+                    t
+                } else {
+                    -(2_f64.powf(10.0 * t - 10.0)) * ((t * 10.0 - 10.75) * (2.0 * PI) / 3.0).sin()
+                }
+            }),
+            Easing::Bounce => in_out(t, |t| 1.0 - bounce_out(1.0 - t)),
+            Easing::Back => in_out(t, |t| {
+                let c1 = 1.70158;
+                let c3 = c1 + 1.0;
+                c3 * t.powi(3) - c1 * t * t
+            }),
+            Easing::CubicBezier(x1, y1, x2, y2) => cubic_bezier(x1, y1, x2, y2, t),
+        }
+    }
+}
+
+/// Eases in for the first half with `ease_in`,
+/// and eases out for the rest with its mirror.
+fn in_out(t: f64, ease_in: impl Fn(f64) -> f64) -> f64 {
+    if t < 0.5 {
+        ease_in(t * 2.0) / 2.0
+    } else {
+        1.0 - ease_in((1.0 - t) * 2.0) / 2.0
+    }
+}
+
+fn bounce_out(t: f64) -> f64 {
+    let n1 = 7.5625;
+    let d1 = 2.75;
+    if t < 1.0 / d1 {
+        n1 * t * t
+    } else if t < 2.0 / d1 {
+        let t = t - 1.5 / d1;
+        n1 * t * t + 0.75
+    } else if t < 2.5 / d1 {
+        let t = t - 2.25 / d1;
+        n1 * t * t + 0.9375
+    } else {
+        let t = t - 2.625 / d1;
+        n1 * t * t + 0.984375
+    }
+}
+
+/// Finds `s` for which the curve is at `x`, and returns `y` at `s`.
+/// Since `x` always grows with `s` (`x1` and `x2` being within
+/// `0.0` and `1.0`), bisection is enough to find it.
+fn cubic_bezier(x1: f64, y1: f64, x2: f64, y2: f64, x: f64) -> f64 {
+    let bezier = |p1: f64, p2: f64, s: f64| {
+        let r = 1.0 - s;
+        3.0 * r * r * s * p1 + 3.0 * r * s * s * p2 + s * s * s
+    };
+
+    let (mut lo, mut hi) = (0.0, 1.0);
+    let mut s = x;
+    for _ in 0..32 {
+        let bx = bezier(x1, x2, s);
+        if (bx - x).abs() < 1e-7 {
+            break;
+        }
+        if bx < x {
+            lo = s;
+        } else {
+            hi = s;
+        }
+        s = (lo + hi) / 2.0;
+    }
+    bezier(y1, y2, s)
+}
+
+const NAMES: [(&str, Easing); 9] = [
+    ("linear", Easing::Linear),
+    ("quad", Easing::Quad),
+    ("cubic", Easing::Cubic),
+    ("quart", Easing::Quart),
+    ("sine", Easing::Sine),
+    ("expo", Easing::Expo),
+    ("elastic", Easing::Elastic),
+    ("bounce", Easing::Bounce),
+    ("back", Easing::Back),
+];
+
+impl FromStr for Easing {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if let Some((_, easing)) = NAMES.iter().find(|(name, _)| *name == s) {
+            return Ok(*easing);
+        }
+
+        let err = || format!("No such easing: {}", s);
+        let args = s
+            .strip_prefix("cubic-bezier(")
+            .and_then(|args| args.strip_suffix(')'))
+            .ok_or_else(err)?;
+        let args: Vec<f64> = args
+            .split(',')
+            .map(|v| v.trim().parse::<f64>())
+            .collect::<Result<_, _>>()
+            .map_err(|_| err())?;
+
+        match args[..] {
+            [_, y1, _, y2] if !(y1.is_finite() && y2.is_finite()) => {
+                Err(format!("y1 and y2 must be numbers: {}", s))
+            }
+            [x1, y1, x2, y2] if (0.0..=1.0).contains(&x1) && (0.0..=1.0).contains(&x2) => {
+                Ok(Easing::CubicBezier(x1, y1, x2, y2))
+            }
+            [_, _, _, _] => Err(format!("x1 and x2 must be between 0 and 1: {}", s)),
+            _ => Err(err()),
+        }
+    }
+}
+
+impl TryFrom<String> for Easing {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl fmt::Display for Easing {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Easing::CubicBezier(x1, y1, x2, y2) => {
+                write!(f, "cubic-bezier({}, {}, {}, {})", x1, y1, x2, y2)
+            }
+            easing => {
+                let (name, _) = NAMES
+                    .iter()
+                    .find(|(_, e)| *e == easing)
+                    .unwrap_or(&NAMES[0]);
+                write!(f, "{}", name)
+            }
+        }
+    }
+}
+
+impl From<Easing> for String {
+    fn from(easing: Easing) -> String {
+        easing.to_string()
+    }
+}
+
+/// Easing for each `GraphType`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct EasingConfig {
+    pub radio: Easing,
+    pub bars: Easing,
+    pub solar: Easing,
+    pub area: Easing,
+    pub mirror: Easing,
+    pub dots: Easing,
+    pub stems: Easing,
+}
+
+impl Default for EasingConfig {
+    fn default() -> EasingConfig {
+        EasingConfig {
+            radio: Easing::Quad,
+            bars: Easing::Linear,
+            solar: Easing::Linear,
+            area: Easing::Quad,
+            mirror: Easing::Quad,
+            dots: Easing::Linear,
+            stems: Easing::Linear,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_easing_starts_and_ends_at_the_ends() {
+        let mut easings: Vec<Easing> = NAMES.iter().map(|(_, e)| *e).collect();
+        easings.push(Easing::CubicBezier(0.42, 0.0, 0.58, 1.0));
+        for easing in easings {
+            assert!(easing.apply(0.0).abs() < 1e-6, "{}", easing);
+            assert!((easing.apply(1.0) - 1.0).abs() < 1e-6, "{}", easing);
+            assert!((easing.apply(0.5) - 0.5).abs() < 1e-6, "{}", easing);
+        }
+    }
+
+    #[test]
+    fn quad_is_the_one_radio_had() {
+        for t in [0.1, 0.3, 0.5, 0.7, 0.9].iter() {
+            let t = *t;
+            let expected = if t < 0.5 {
+                t * t * 2.0
+            } else {
+                t * (4.0 - t * 2.0) - 1.0
+            };
+            assert!((Easing::Quad.apply(t) - expected).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn cubic_bezier_as_linear() {
+        let linear = Easing::CubicBezier(0.0, 0.0, 1.0, 1.0);
+        assert!((linear.apply(0.3) - 0.3).abs() < 1e-5);
+    }
+
+    #[test]
+    fn parse_and_print() {
+        assert_eq!("bounce".parse(), Ok(Easing::Bounce));
+        assert_eq!(
+            "cubic-bezier(0.4, 0, 0.2, 1)".parse(),
+            Ok(Easing::CubicBezier(0.4, 0.0, 0.2, 1.0))
+        );
+        assert!("cubic-bezier(1.4, 0, 0.2, 1)".parse::<Easing>().is_err());
+        assert!("cubic-bezier(0, NaN, 1, inf)".parse::<Easing>().is_err());
+        assert!("cubic-bezier(0, 0, 1, -inf)".parse::<Easing>().is_err());
+        assert!("wobble".parse::<Easing>().is_err());
+        assert_eq!(Easing::Back.to_string(), "back");
+        assert_eq!(
+            Easing::CubicBezier(0.4, 0.0, 0.2, 1.0).to_string(),
+            "cubic-bezier(0.4, 0, 0.2, 1)"
+        );
+    }
+}
@@ -0,0 +1,213 @@
+use serde::{Deserialize, Serialize};
+
+use crate::perlin::{NoiseAlgorithm, NoiseSource};
+
+/// How the octaves are composed (`fractal.type` in `Config`).
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum FractalType {
+    /// A single octave. Same as using the noise source as it is.
+    #[default]
+    None,
+    /// Fractional Brownian motion. Smooth swells.
+    Fbm,
+    /// Ridged multifractal. Sharp, mountain-like peaks.
+    Ridged,
+    /// Absolute valued octaves. Puffy, rounded bumps.
+    Billow,
+    /// fBm sampled at coordinates warped by another fBm.
+    Turbulence,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct FractalConfig {
+    #[serde(rename = "type")]
+    pub kind: FractalType,
+    /// Number of layers to be summed up.
+    pub octaves: usize,
+    /// How much the frequency grows for each octave.
+    pub lacunarity: f64,
+    /// How much the amplitude shrinks for each octave.
+    pub persistence: f64,
+    /// Frequency of the first octave.
+    pub frequency: f64,
+    /// How far the coordinates are pushed in `Turbulence`.
+    pub warp: f64,
+}
+
+impl Default for FractalConfig {
+    fn default() -> Self {
+        FractalConfig {
+            kind: FractalType::None,
+            octaves: 6,
+            lacunarity: 2.0,
+            persistence: 0.5,
+            frequency: 1.0,
+            warp: 1.0,
+        }
+    }
+}
+
+/// Every octave is shifted by this much so that
+/// the octaves do not all line up at the origin.
+const OCTAVE_SHIFT: f64 = 31.7;
+
+/// Wraps a `NoiseSource` and composes octaves of it.
+/// Whatever the type, the value stays within `-1.0` and `1.0`
+/// as long as the source does.
+pub struct Fractal {
+    source: Box<dyn NoiseSource>,
+    config: FractalConfig,
+}
+
+impl Fractal {
+    pub fn new(source: Box<dyn NoiseSource>, config: &FractalConfig) -> Fractal {
+        Fractal {
+            source,
+            config: config.clone(),
+        }
+    }
+
+    /// Sums up the octaves, each of which is first
+    /// shaped by `f`, and normalizes the sum.
+    fn octaves<F>(&self, x: f64, y: f64, f: F) -> f64
+    where
+        F: Fn(f64) -> f64,
+    {
+        let mut sum: f64 = 0.0;
+        let mut max: f64 = 0.0;
+        let mut amplitude: f64 = 1.0;
+        let mut frequency: f64 = self.config.frequency;
+
+        for i in 0..self.config.octaves.max(1) {
+            let shift = i as f64 * OCTAVE_SHIFT;
+            let n = self
+                .source
+                .get(x * frequency + shift, y * frequency + shift);
+            sum += f(n) * amplitude;
+            max += amplitude;
+            amplitude *= self.config.persistence;
+            frequency *= self.config.lacunarity;
+        }
+
+        sum / max
+    }
+
+    fn fbm(&self, x: f64, y: f64) -> f64 {
+        self.octaves(x, y, |n| n)
+    }
+
+    fn billow(&self, x: f64, y: f64) -> f64 {
+        self.octaves(x, y, |n| n.abs() * 2.0 - 1.0)
+    }
+
+    /// Each octave is inverted around `0.0` so that valleys
+    /// become sharp ridges, and it is weighted by the
+    /// previous octave so that details gather on the ridges.
+    fn ridged(&self, x: f64, y: f64) -> f64 {
+        let mut sum: f64 = 0.0;
+        let mut max: f64 = 0.0;
+        let mut amplitude: f64 = 1.0;
+        let mut frequency: f64 = self.config.frequency;
+        let mut weight: f64 = 1.0;
+
+        for i in 0..self.config.octaves.max(1) {
+            let shift = i as f64 * OCTAVE_SHIFT;
+            let n = self
+                .source
+                .get(x * frequency + shift, y * frequency + shift);
+            let signal = (1.0 - n.abs()).powi(2) * weight;
+            weight = signal.clamp(0.0, 1.0);
+            sum += signal * amplitude;
+            max += amplitude;
+            amplitude *= self.config.persistence;
+            frequency *= self.config.lacunarity;
+        }
+
+        (sum / max) * 2.0 - 1.0
+    }
+
+    fn turbulence(&self, x: f64, y: f64) -> f64 {
+        let dx = self.fbm(x + 5.2, y + 1.3);
+        let dy = self.fbm(x + 1.7, y + 9.2);
+        self.fbm(x + dx * self.config.warp, y + dy * self.config.warp)
+    }
+}
+
+impl NoiseSource for Fractal {
+    fn get(&self, x: f64, y: f64) -> f64 {
+        match self.config.kind {
+            FractalType::None => {
+                let frequency = self.config.frequency;
+                self.source.get(x * frequency, y * frequency)
+            }
+            FractalType::Fbm => self.fbm(x, y),
+            FractalType::Ridged => self.ridged(x, y),
+            FractalType::Billow => self.billow(x, y),
+            FractalType::Turbulence => self.turbulence(x, y),
+        }
+    }
+
+    fn seed(&self) -> u32 {
+        self.source.seed()
+    }
+
+    fn algorithm(&self) -> NoiseAlgorithm {
+        self.source.algorithm()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::perlin::SeededNoise;
+
+    fn fractal(kind: FractalType) -> Fractal {
+        let config = FractalConfig {
+            kind,
+            ..FractalConfig::default()
+        };
+        Fractal::new(
+            Box::new(SeededNoise::new(NoiseAlgorithm::Perlin, 3)),
+            &config,
+        )
+    }
+
+    #[test]
+    fn none_is_the_source() {
+        let source = SeededNoise::new(NoiseAlgorithm::Perlin, 3);
+        let f = fractal(FractalType::None);
+        assert_eq!(f.get(0.3, 1.9), source.get(0.3, 1.9));
+    }
+
+    #[test]
+    fn stays_within_range() {
+        let kinds = [
+            FractalType::None,
+            FractalType::Fbm,
+            FractalType::Ridged,
+            FractalType::Billow,
+            FractalType::Turbulence,
+        ];
+        for algorithm in NoiseAlgorithm::ALL.iter() {
+            for kind in kinds.iter() {
+                let config = FractalConfig {
+                    kind: *kind,
+                    ..FractalConfig::default()
+                };
+                let f = Fractal::new(Box::new(SeededNoise::new(*algorithm, 3)), &config);
+                for i in 0..200 {
+                    let v = f.get(i as f64 * 0.37, 2.5);
+                    assert!(
+                        (-1.0..=1.0).contains(&v),
+                        "{:?} {:?}: {}",
+                        algorithm,
+                        kind,
+                        v
+                    );
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,467 @@
+//! Where to draw things for each `GraphType`.
+//! Renderers only have to feed the results to the canvas.
+
+use lerp::Lerp;
+use log::debug;
+use std::f64::consts::PI;
+
+use crate::types::Point;
+
+/// Given `elapsed` milliseconds into a cycle of `cycle` milliseconds,
+/// it simply calculates for the ratio.
+/// `1.0` signifies it is at the end of the cycle,
+/// and `0.0` still at the beginning of the cycle.
+pub fn relative_pos_full(elapsed: f64, cycle: f64) -> f64 {
+    if cycle > 0.0 {
+        (elapsed / cycle).clamp(0.0, 1.0)
+    } else {
+        0.0
+    }
+}
+
+/// Similar to `relative_pos_full`, but `1.0` denotes
+/// it is in the middle of the cycle.
+/// `0.5` means it is either approaching the middle
+/// or approaching the end of the cycle.
+/// `0.0` means it is either at the beginning,
+/// or at the end of the cycle.
+pub fn relative_pos_half(elapsed: f64, cycle: f64) -> f64 {
+    let pos: f64 = relative_pos_full(elapsed, cycle);
+    if pos > 0.5 {
+        2.0 - pos * 2.0
+    } else {
+        pos * 2.0
+    }
+}
+
+/// How tall the wave can grow for the given canvas height.
+pub fn amplify_value(height: f64) -> f64 {
+    height * 0.2
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Rect {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+/// Where the `i`th of `segments` points goes horizontally.
+/// Points are spread evenly over the canvas whatever
+/// the segments or the reference width of the wave are.
+fn segment_x(i: usize, segments: usize, width: f64) -> f64 {
+    0_f64.lerp(width, i as f64 / segments as f64).round() // MIN.lerp(MAX, ratio)
+}
+
+/// Vertices of the line for `GraphType::Radio`.
+/// It starts from the left edge in the middle,
+/// and ends at the right edge in the middle.
+/// `rel_pos` grows the wave from the flat line
+/// (`0.0`) to its full height (`1.0`).
+pub fn radio_path(points: &[Point], width: f64, height: f64, rel_pos: f64) -> Vec<(f64, f64)> {
+    let half_h: f64 = height / 2.0;
+    let amplify: f64 = amplify_value(height);
+
+    let mut path = Vec::with_capacity(points.len() + 2);
+    path.push((0_f64, half_h.round()));
+
+    for (i, p) in points.iter().enumerate() {
+        let x = segment_x(i, points.len(), width);
+        let y = (0_f64.lerp(p.y, rel_pos) * amplify + half_h).round();
+        path.push((x, y));
+    }
+
+    path.push((width, half_h));
+    path
+}
+
+/// Bars for `GraphType::Bars`, each of which is
+/// stretching both upwards and downwards from the middle.
+/// `rel_pos` moves the bars from `points_prev` (`0.0`)
+/// to `points` (`1.0`).
+pub fn bars(
+    points: &[Point],
+    points_prev: &[Point],
+    width: f64,
+    height: f64,
+    rel_pos: f64,
+) -> Vec<Rect> {
+    let segments = points.len();
+    let unit_w: f64 = ((width / segments as f64) - 2.0).round().max(1.0);
+    let half_h: f64 = (height / 2.0).round();
+    let amplify: f64 = amplify_value(height);
+
+    points
+        .iter()
+        .zip(points_prev.iter())
+        .enumerate()
+        .map(|(i, (p, prev))| {
+            let x = segment_x(i, segments, width);
+            // We basically want to gradually move the bar
+            // from the previous position to the current.
+            // There, we need "rel_pos".
+            let y = (prev.y.lerp(p.y, rel_pos) * amplify).round().abs();
+            Rect {
+                x,
+                y: half_h - y,
+                width: unit_w,
+                height: y * 2.0,
+            }
+        })
+        .collect()
+}
+
+/// `path` (of `radio_path`) upside down, for `GraphType::Mirror`.
+pub fn mirror_path(path: &[(f64, f64)], height: f64) -> Vec<(f64, f64)> {
+    path.iter().map(|(x, y)| (*x, height - y)).collect()
+}
+
+/// Size of the dots for `GraphType::Dots` and `GraphType::Stems`.
+pub fn dot_radius(width: f64, segments: usize) -> f64 {
+    (width / segments.max(1) as f64 * 0.35).max(1.0)
+}
+
+/// One dot of `GraphType::Dots`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Dot {
+    pub x: f64,
+    pub y: f64,
+}
+
+/// Dots for `GraphType::Dots`, one column for each point.
+/// Each column has a dot in the middle, and as many dots
+/// upwards and downwards as the value reaches.
+/// `rel_pos` works the same as `bars`.
+pub fn dots(
+    points: &[Point],
+    points_prev: &[Point],
+    width: f64,
+    height: f64,
+    rel_pos: f64,
+) -> Vec<Dot> {
+    let segments = points.len();
+    let pitch = (width / segments as f64).max(1.0);
+    let half_h: f64 = (height / 2.0).round();
+    let amplify: f64 = amplify_value(height);
+    let max_rows = (half_h / pitch).floor() as usize;
+
+    points
+        .iter()
+        .zip(points_prev.iter())
+        .enumerate()
+        .flat_map(|(i, (p, prev))| {
+            let x = (segment_x(i, segments, width) + pitch / 2.0).round();
+            let value = (prev.y.lerp(p.y, rel_pos) * amplify).abs();
+            let rows = ((value / pitch).round() as usize).min(max_rows);
+            let mut column = vec![Dot { x, y: half_h }];
+            for row in 1..=rows {
+                let dy = (row as f64 * pitch).round();
+                column.push(Dot { x, y: half_h - dy });
+                column.push(Dot { x, y: half_h + dy });
+            }
+            column
+        })
+        .collect()
+}
+
+/// One line of `GraphType::Stems`, from `base` up to `top`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Stem {
+    pub x: f64,
+    pub base: f64,
+    pub top: f64,
+    /// How tall it is, between `0.0` and `1.0`.
+    pub value: f64,
+}
+
+/// Lines for `GraphType::Stems`, all standing
+/// on the same base line near the bottom.
+/// `rel_pos` works the same as `bars`.
+pub fn stems(
+    points: &[Point],
+    points_prev: &[Point],
+    width: f64,
+    height: f64,
+    rel_pos: f64,
+) -> Vec<Stem> {
+    let segments = points.len();
+    let pitch = width / segments as f64;
+    let base = (height * 0.9).round();
+    let max_length = height * 0.8;
+
+    points
+        .iter()
+        .zip(points_prev.iter())
+        .enumerate()
+        .map(|(i, (p, prev))| {
+            let value = prev
+                .normalize()
+                .y
+                .lerp(p.normalize().y, rel_pos)
+                .clamp(0.0, 1.0);
+            Stem {
+                x: (segment_x(i, segments, width) + pitch / 2.0).round(),
+                base,
+                top: (base - value * max_length).round(),
+                value,
+            }
+        })
+        .collect()
+}
+
+/// Sizes for `GraphType::Solar` which only depend
+/// on the canvas and the number of segments.
+#[derive(Clone, Debug)]
+pub struct SolarInfo {
+    pub segments: usize,
+    pub num_of_bars: f64,
+    pub radius_inner: f64,
+    pub max_length: f64,
+    pub size: f64,
+    pub angle_step: f64,
+}
+
+impl SolarInfo {
+    /// Draws a bar for every 2.5 segments.
+    pub fn new(canvas_height: f64, segments: usize) -> SolarInfo {
+        let num_of_bars = (segments as f64 * 0.4).round().max(1.0);
+        let angle_step = 360.0 / num_of_bars;
+        let diameter = canvas_height * 0.99;
+        let radius = diameter / 2.0;
+        let radius_inner = radius * 0.55;
+        let max_length = radius - radius_inner;
+        let size = diameter * PI / num_of_bars * 0.2;
+
+        debug!("(SolarInfo) num_of_bars: {}", num_of_bars);
+        debug!("(SolarInfo) size: {}", size);
+
+        SolarInfo {
+            segments,
+            num_of_bars,
+            radius_inner,
+            max_length,
+            size,
+            angle_step,
+        }
+    }
+}
+
+/// One bar for `GraphType::Solar`.
+/// `rect` is a horizontal bar at 3 o'clock position
+/// relative to the center, and is meant to be drawn
+/// after rotating the canvas by `angle` (in radian).
+#[derive(Clone, Debug, PartialEq)]
+pub struct SolarBar {
+    pub angle: f64,
+    pub rect: Rect,
+}
+
+/// The idea is to draw a horizontal bar
+/// at 3 o'clock position. However,
+/// everytime we draw the bar, we will
+/// rotate canvas in different angle.
+/// So, it is always a horizontal bar,
+/// but because it is rotated,
+/// we will have the effect of drawing
+/// a flower like bars.
+pub fn solar_bars(
+    sol: &SolarInfo,
+    points: &[Point],
+    points_prev: &[Point],
+    rel_pos: f64,
+) -> Vec<SolarBar> {
+    let num_of_bars = (sol.num_of_bars as usize).min(points.len().min(points_prev.len()));
+
+    (0..num_of_bars)
+        .map(|i| {
+            let angle: f64 = i as f64 * sol.angle_step;
+            let x = sol.radius_inner.round();
+            let width: f64 = points_prev[i]
+                .normalize()
+                .y
+                .lerp(points[i].normalize().y, rel_pos);
+            let width = 0_f64.lerp(sol.max_length, width).round();
+            let height = sol.size.round();
+            let y = -(height / 2.0).round();
+
+            SolarBar {
+                angle: angle * PI / 180.0,
+                rect: Rect {
+                    x,
+                    y,
+                    width,
+                    height,
+                },
+            }
+        })
+        .collect()
+}
+
+/// `rect` drawn after moving to `(x, y)`
+/// and rotating by `angle` (in radian).
+#[derive(Clone, Debug, PartialEq)]
+pub struct Placed {
+    pub x: f64,
+    pub y: f64,
+    pub angle: f64,
+    pub rect: Rect,
+}
+
+fn lerp_rect(a: &Rect, b: &Rect, t: f64) -> Rect {
+    Rect {
+        x: a.x.lerp(b.x, t),
+        y: a.y.lerp(b.y, t),
+        width: a.width.lerp(b.width, t),
+        height: a.height.lerp(b.height, t),
+    }
+}
+
+/// Grows `bars` out of the vertices of `path` (of `radio_path`)
+/// as `progress` goes from `0.0` to `1.0`.
+pub fn morph_line_to_bars(path: &[(f64, f64)], bars: &[Rect], progress: f64) -> Vec<Rect> {
+    // The first vertex is the left edge, not a point.
+    path.iter()
+        .skip(1)
+        .zip(bars.iter())
+        .map(|((x, y), bar)| {
+            let dot = Rect {
+                x: *x,
+                y: *y,
+                width: 0.0,
+                height: 0.0,
+            };
+            lerp_rect(&dot, bar, progress)
+        })
+        .collect()
+}
+
+/// Folds `bars` into the ring of `solar` around `center`
+/// as `progress` goes from `0.0` to `1.0`.
+/// Bars which have no place in the ring are left out.
+pub fn morph_bars_to_solar(
+    bars: &[Rect],
+    solar: &[SolarBar],
+    center: (f64, f64),
+    progress: f64,
+) -> Vec<Placed> {
+    bars.iter()
+        .zip(solar.iter())
+        .map(|(bar, sol)| {
+            // Lying on its side, so that rotating
+            // by a quarter stands it up as it was.
+            let lying = Rect {
+                x: -bar.height / 2.0,
+                y: -bar.width / 2.0,
+                width: bar.height,
+                height: bar.width,
+            };
+            Placed {
+                x: (bar.x + bar.width / 2.0).lerp(center.0, progress),
+                y: (bar.y + bar.height / 2.0).lerp(center.1, progress),
+                angle: (PI / 2.0).lerp(sol.angle, progress),
+                rect: lerp_rect(&lying, &sol.rect, progress),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::{NORMAL_WIDTH, SEGMENTS};
+
+    fn flat(y: f64) -> Vec<Point> {
+        (0..SEGMENTS)
+            .map(|i| Point {
+                x: i as f64 * NORMAL_WIDTH / SEGMENTS as f64,
+                y,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn relative_pos() {
+        assert_eq!(relative_pos_full(0.0, 400.0), 0.0);
+        assert_eq!(relative_pos_full(400.0, 400.0), 1.0);
+        assert_eq!(relative_pos_half(0.0, 400.0), 0.0);
+        assert_eq!(relative_pos_half(100.0, 400.0), 0.5);
+        assert_eq!(relative_pos_half(200.0, 400.0), 1.0);
+        assert_eq!(relative_pos_half(400.0, 400.0), 0.0);
+    }
+
+    #[test]
+    fn radio_path_starts_and_ends_in_the_middle() {
+        let path = radio_path(&flat(1.0), 550.0, 200.0, 0.0);
+        assert_eq!(path.len(), SEGMENTS + 2);
+        assert_eq!(path[0], (0.0, 100.0));
+        assert_eq!(path[path.len() - 1], (550.0, 100.0));
+        assert!(path.iter().all(|(_, y)| *y == 100.0));
+    }
+
+    #[test]
+    fn dots_grow_from_the_middle() {
+        let flat_dots = dots(&flat(0.0), &flat(0.0), 550.0, 200.0, 1.0);
+        assert_eq!(flat_dots.len(), SEGMENTS);
+        assert!(flat_dots.iter().all(|d| d.y == 100.0));
+
+        // 40px each way with the pitch of 5px.
+        let full = dots(&flat(1.0), &flat(0.0), 550.0, 200.0, 1.0);
+        assert_eq!(full.len(), SEGMENTS * 17);
+        assert!(full.iter().all(|d| d.y >= 60.0 && d.y <= 140.0));
+    }
+
+    #[test]
+    fn stems_stand_on_the_base() {
+        let stems = stems(&flat(1.0), &flat(-1.0), 550.0, 200.0, 0.5);
+        assert_eq!(stems.len(), SEGMENTS);
+        assert!(stems.iter().all(|s| s.base == 180.0 && s.top == 100.0));
+    }
+
+    #[test]
+    fn morphs_end_where_they_should() {
+        let points = flat(0.5);
+        let path = radio_path(&points, 550.0, 200.0, 1.0);
+        let bars = bars(&points, &points, 550.0, 200.0, 1.0);
+        let start = morph_line_to_bars(&path, &bars, 0.0);
+        assert_eq!(
+            (start[3].x, start[3].y, start[3].height),
+            (path[4].0, path[4].1, 0.0)
+        );
+        assert_eq!(morph_line_to_bars(&path, &bars, 1.0), bars);
+
+        let sol = SolarInfo::new(200.0, SEGMENTS);
+        let solar = solar_bars(&sol, &points, &points, 1.0);
+        let folded = morph_bars_to_solar(&bars, &solar, (275.0, 100.0), 1.0);
+        assert_eq!(folded.len(), solar.len());
+        assert_eq!(folded[5].rect, solar[5].rect);
+        assert_eq!((folded[5].x, folded[5].angle), (275.0, solar[5].angle));
+
+        // Standing where the bar was. Rotated by a quarter,
+        // the right end of the rect comes to the bottom.
+        let standing = &morph_bars_to_solar(&bars, &solar, (275.0, 100.0), 0.0)[5];
+        let bottom = standing.y + standing.rect.width / 2.0;
+        assert_eq!(standing.x, bars[5].x + bars[5].width / 2.0);
+        assert_eq!(bottom, bars[5].y + bars[5].height);
+    }
+
+    #[test]
+    fn bars_move_from_previous_to_current() {
+        let prev = flat(0.0);
+        let current = flat(-1.0);
+        let start = bars(&current, &prev, 550.0, 200.0, 0.0);
+        let end = bars(&current, &prev, 550.0, 200.0, 1.0);
+        assert_eq!(start[0].height, 0.0);
+        assert_eq!(end[0].height, 80.0);
+        assert_eq!(end[0].y, 60.0);
+    }
+
+    #[test]
+    fn solar_bars_go_round() {
+        let sol = SolarInfo::new(200.0, SEGMENTS);
+        let bars = solar_bars(&sol, &flat(1.0), &flat(1.0), 1.0);
+        assert_eq!(bars.len(), 44);
+        assert_eq!(bars[0].angle, 0.0);
+        assert_eq!(bars[0].rect.width, sol.max_length.round());
+    }
+}
@@ -0,0 +1,439 @@
+use std::any::Any;
+use std::f64::consts::PI;
+
+use crate::constants::FONT_FAMILY;
+use crate::controls::Controls;
+use crate::graphics::Graphics;
+use crate::history::History;
+use crate::stats::{self, Metric, Readout, ReadoutMode, Stats};
+use crate::surface::Surface;
+use crate::theme::Theme;
+use crate::types::Point;
+
+pub struct ControlGraphics {
+    surface: Box<dyn Surface>,
+    pub width: f64,
+    pub height: f64,
+    bgcolor: String,
+    color: String,
+    /// For the knob being dragged.
+    accent: String,
+    /// Font family.
+    font: String,
+    /// The largest the readout is drawn in.
+    font_size: u32,
+    metrics: Vec<Metric>,
+    mode: ReadoutMode,
+    /// Drawn as the sparkline right of the readout when given.
+    history: Option<History>,
+    /// Drawn to the right of the readout when given.
+    controls: Option<Controls>,
+}
+
+impl Graphics for ControlGraphics {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn surface(&mut self) -> &mut dyn Surface {
+        self.surface.as_mut()
+    }
+
+    fn size(&self) -> (f64, f64) {
+        (self.width, self.height)
+    }
+
+    fn bgcolor(&self) -> &str {
+        &self.bgcolor
+    }
+
+    fn set_theme(&mut self, theme: &Theme) {
+        self.bgcolor = theme.background.clone();
+        self.color = theme.secondary.clone();
+        self.accent = theme.accent.clone();
+        self.font = theme.font.clone();
+        self.fit_font();
+    }
+
+    fn reset(&mut self, width: f64, height: f64) {
+        self.width = width;
+        self.height = height;
+        self.fit_font();
+    }
+}
+
+impl ControlGraphics {
+    pub fn new(
+        surface: Box<dyn Surface>,
+        width: f64,
+        height: f64,
+        bgcolor: &str,
+        color: &str,
+    ) -> ControlGraphics {
+        let mut g = ControlGraphics {
+            surface,
+            width,
+            height,
+            bgcolor: bgcolor.into(),
+            color: color.into(),
+            accent: color.into(),
+            font: FONT_FAMILY.into(),
+            font_size: 0,
+            metrics: vec![Metric::Value],
+            mode: ReadoutMode::Compact,
+            history: None,
+            controls: None,
+        };
+        g.fit_font();
+        g
+    }
+
+    /// The text fills most of the height.
+    fn fit_font(&mut self) {
+        self.font_size = (self.height * 0.8) as u32;
+    }
+
+    /// The same settings drawing on another surface.
+    pub fn with_surface(&self, surface: Box<dyn Surface>) -> ControlGraphics {
+        ControlGraphics {
+            surface,
+            width: self.width,
+            height: self.height,
+            bgcolor: self.bgcolor.clone(),
+            color: self.color.clone(),
+            accent: self.accent.clone(),
+            font: self.font.clone(),
+            font_size: self.font_size,
+            metrics: self.metrics.clone(),
+            mode: self.mode,
+            history: self.history.clone(),
+            controls: self.controls.clone(),
+        }
+    }
+
+    /// What the readout shows, and how.
+    pub fn set_readout(&mut self, metrics: Vec<Metric>, mode: ReadoutMode) {
+        self.metrics = metrics;
+        self.mode = mode;
+    }
+
+    /// Keeps the values of the last `cycles` cycles for the sparkline.
+    /// No sparkline when `0`.
+    pub fn set_history(&mut self, cycles: usize) {
+        self.history = if cycles > 0 {
+            Some(History::new(cycles))
+        } else {
+            None
+        };
+    }
+
+    /// Called once a cycle with its points.
+    pub fn push_history(&mut self, points: &[Point]) {
+        if let Some(history) = &mut self.history {
+            history.push(stats::control_value(points));
+        }
+    }
+
+    pub fn set_controls(&mut self, controls: Controls) {
+        self.controls = Some(controls);
+    }
+
+    pub fn controls_mut(&mut self) -> Option<&mut Controls> {
+        self.controls.as_mut()
+    }
+
+    pub fn color(&self) -> &str {
+        &self.color
+    }
+
+    pub fn render_control(&mut self, points: &[Point], readout: &Readout) {
+        self.render_readout(points, readout);
+        self.render_sliders();
+    }
+
+    /// Left of the sliders, or the whole width without them,
+    /// sharing it with the sparkline. The font shrinks for the text to fit,
+    /// guessing the width of a character from the font size.
+    fn render_readout(&mut self, points: &[Point], readout: &Readout) {
+        const PAD: f64 = 5.0;
+        const CHAR_WIDTH: f64 = 0.6;
+        let stats = Stats::of(points);
+        let texts: Vec<String> = self
+            .metrics
+            .iter()
+            .map(|m| m.text(points, &stats, readout))
+            .collect();
+        let width = match &self.controls {
+            Some(_) => Controls::readout_width(self.width, self.height),
+            None => self.width,
+        } - PAD * 2.0;
+        let sparkline = match &self.history {
+            Some(_) => width * 0.4,
+            None => 0.0,
+        };
+        let width = width - sparkline;
+        let max = self.font_size as f64;
+
+        // Each is `(text, x, y)` with the font size.
+        let (lines, font_size): (Vec<(String, f64, f64)>, f64) = match self.mode {
+            ReadoutMode::Compact => {
+                let text = texts.join("  ");
+                let chars = text.chars().count().max(1) as f64;
+                let size = max.min(width / (chars * CHAR_WIDTH)).floor();
+                let y = self.height / 2.0 + size * 0.375;
+                (vec![(text, PAD, y)], size)
+            }
+            ReadoutMode::Expanded => {
+                // No smaller than 12px unless a single row is.
+                let rows = ((self.height / 15.0).floor() as usize).clamp(1, texts.len().max(1));
+                let columns = texts.len().div_ceil(rows);
+                let row_height = self.height / rows as f64;
+                let column_width = width / columns.max(1) as f64;
+                let chars = texts.iter().map(|t| t.chars().count()).max().unwrap_or(1) as f64;
+                let size = (row_height * 0.8)
+                    .min(column_width / (chars * CHAR_WIDTH))
+                    .floor();
+                let lines = texts
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, text)| {
+                        let (column, row) = (i / rows, i % rows);
+                        let x = PAD + column as f64 * column_width;
+                        let y = (row as f64 + 0.5) * row_height + size * 0.375;
+                        (text, x, y)
+                    })
+                    .collect();
+                (lines, size)
+            }
+        };
+
+        let ctx = self.surface.as_mut();
+        ctx.save();
+        ctx.set_fill_style(self.color.as_str());
+        ctx.set_font(format!("{}px {}", font_size, self.font).as_str());
+        for (text, x, y) in lines {
+            ctx.fill_text(text.as_str(), x, y);
+        }
+        ctx.restore();
+
+        if sparkline > 0.0 {
+            let top = self.height * 0.2;
+            self.render_sparkline(
+                PAD * 2.0 + width,
+                top,
+                sparkline - PAD,
+                self.height - top * 2.0,
+            );
+        }
+    }
+
+    /// Scaled to the values kept, with the latest at the right end
+    /// marked by a dot. Flat in the middle while they are all the same.
+    fn render_sparkline(&mut self, x: f64, y: f64, width: f64, height: f64) {
+        let history = match &self.history {
+            Some(history) if !history.is_empty() => history,
+            _ => return,
+        };
+        let (min, max) = history.range().unwrap_or((0.0, 0.0));
+        let span = max - min;
+        let step = width / history.capacity().saturating_sub(1).max(1) as f64;
+        // Until it is full, it starts part way.
+        let offset = history.capacity() - history.len();
+        let points: Vec<(f64, f64)> = history
+            .values()
+            .enumerate()
+            .map(|(i, v)| {
+                let ratio = if span > 1e-9 { (v - min) / span } else { 0.5 };
+                (x + (offset + i) as f64 * step, y + height * (1.0 - ratio))
+            })
+            .collect();
+
+        let ctx = self.surface.as_mut();
+        ctx.save();
+        ctx.set_stroke_style(self.color.as_str());
+        ctx.begin_path();
+        for (i, (px, py)) in points.iter().enumerate() {
+            if i == 0 {
+                ctx.move_to(*px, *py);
+            } else {
+                ctx.line_to(*px, *py);
+            }
+        }
+        ctx.stroke();
+        if let Some((px, py)) = points.last() {
+            ctx.set_fill_style(self.accent.as_str());
+            ctx.begin_path();
+            ctx.arc(*px, *py, (height * 0.08).max(1.5), 0.0, PI * 2.0);
+            ctx.fill();
+        }
+        ctx.restore();
+    }
+
+    /// Each slider has the label and the value above the track,
+    /// which is filled up to the knob.
+    fn render_sliders(&mut self) {
+        let controls = match &self.controls {
+            Some(controls) => controls,
+            None => return,
+        };
+        let ctx = self.surface.as_mut();
+        for (i, slider) in controls.sliders.iter().enumerate() {
+            let l = controls.layout(i, self.width, self.height);
+            let track = l.track;
+            let filled = track.width * slider.ratio();
+            let active = controls.active() == Some(i);
+            ctx.save();
+            ctx.set_fill_style(self.color.as_str());
+            ctx.set_font(format!("{}px {}", l.font_size, self.font).as_str());
+            let text = format!(
+                "{} {}",
+                slider.param.label(),
+                slider.param.format(slider.value)
+            );
+            ctx.fill_text(text.as_str(), track.x, l.text_y);
+            ctx.set_global_alpha(0.3);
+            ctx.fill_rect(track.x, track.y, track.width, track.height);
+            ctx.set_global_alpha(1.0);
+            ctx.fill_rect(track.x, track.y, filled, track.height);
+            if active {
+                ctx.set_fill_style(self.accent.as_str());
+            }
+            let radius = if active {
+                l.knob_radius * 1.4
+            } else {
+                l.knob_radius
+            };
+            ctx.begin_path();
+            ctx.arc(
+                track.x + filled,
+                track.y + track.height / 2.0,
+                radius,
+                0.0,
+                PI * 2.0,
+            );
+            ctx.fill();
+            ctx.restore();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::perlin::NoiseAlgorithm;
+    use crate::stats::FrameRate;
+    use crate::surface::mock::{calls, Call, Recorder};
+
+    fn readout() -> Readout {
+        let mut rate = FrameRate::default();
+        rate.record(0.0);
+        rate.record(16.0);
+        Readout {
+            seed: 42,
+            noise: NoiseAlgorithm::Perlin,
+            rate,
+        }
+    }
+
+    #[test]
+    fn render_control() {
+        let mut g =
+            ControlGraphics::new(Box::new(Recorder::default()), 300.0, 20.0, "#222", "#fff");
+        g.render_control(&[Point { x: 0.0, y: -0.5 }], &readout());
+        let calls = calls(g.surface());
+        assert!(calls.contains(&Call::FillText("5.00000".into(), 5.0, 16.0)));
+
+        g.set_theme(&Theme {
+            font: "serif".into(),
+            ..Theme::light()
+        });
+        g.render_control(&[Point { x: 0.0, y: -0.5 }], &readout());
+        let calls = super::tests::calls(g.surface());
+        assert!(calls.contains(&Call::FillStyle(Theme::light().secondary)));
+        assert!(calls.contains(&Call::Font("16px serif".into())));
+
+        g.reset(600.0, 40.0);
+        g.render_control(&[Point { x: 0.0, y: -0.5 }], &readout());
+        let calls = super::tests::calls(g.surface());
+        assert!(calls.contains(&Call::Font("32px serif".into())));
+        assert!(calls.contains(&Call::FillText("5.00000".into(), 5.0, 32.0)));
+        assert!(!calls.iter().any(|c| matches!(c, Call::Arc(..))));
+    }
+
+    #[test]
+    fn render_sliders() {
+        let config: Config = serde_json::from_str(r#"{ "panels": [] }"#).unwrap();
+        let mut controls = Controls::new(&config);
+        let (width, height) = (600.0, 40.0);
+        let track = controls.layout(0, width, height).track;
+        controls.pointer_down(track.x + track.width, height / 2.0, width, height);
+
+        let mut g =
+            ControlGraphics::new(Box::new(Recorder::default()), width, height, "#222", "#fff");
+        g.set_theme(&Theme::high_contrast());
+        g.set_controls(controls);
+        g.render_control(&[Point { x: 0.0, y: -0.5 }], &readout());
+        let calls = calls(g.surface());
+        assert!(calls.contains(&Call::FillText("5.00000".into(), 5.0, 32.0)));
+        assert!(calls.contains(&Call::FillText("amp 2.00".into(), track.x, 18.0)));
+        assert!(calls.contains(&Call::FillStyle(Theme::high_contrast().accent)));
+        let arcs = calls.iter().filter(|c| matches!(c, Call::Arc(..))).count();
+        assert_eq!(arcs, 5);
+    }
+
+    #[test]
+    fn compact_or_expanded() {
+        let points = [Point { x: 0.0, y: 0.5 }, Point { x: 1.0, y: -0.25 }];
+        let metrics = vec![Metric::Max, Metric::Crossings, Metric::Seed, Metric::Fps];
+        let mut g =
+            ControlGraphics::new(Box::new(Recorder::default()), 600.0, 40.0, "#222", "#fff");
+        g.set_readout(metrics.clone(), ReadoutMode::Compact);
+        g.render_control(&points, &readout());
+        let calls = calls(g.surface());
+        let line = "max 0.500  zero 1  seed 42  fps 62";
+        // 590 / (34 * 0.6) is less than 32.
+        assert!(calls.contains(&Call::Font(format!("28px {}", FONT_FAMILY))));
+        assert!(calls.contains(&Call::FillText(line.into(), 5.0, 30.5)));
+
+        g.set_readout(metrics, ReadoutMode::Expanded);
+        g.reset(600.0, 60.0);
+        g.render_control(&points, &readout());
+        let texts: Vec<Call> = super::tests::calls(g.surface())
+            .into_iter()
+            .filter(|c| matches!(c, Call::FillText(..)))
+            .collect();
+        // 4 rows of 15px in a column.
+        assert_eq!(
+            texts,
+            vec![
+                Call::FillText("max 0.500".into(), 5.0, 12.0),
+                Call::FillText("zero 1".into(), 5.0, 27.0),
+                Call::FillText("seed 42".into(), 5.0, 42.0),
+                Call::FillText("fps 62".into(), 5.0, 57.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn sparkline_of_the_past_cycles() {
+        let mut g =
+            ControlGraphics::new(Box::new(Recorder::default()), 300.0, 20.0, "#222", "#fff");
+        g.set_history(3);
+        for y in &[0.1, 0.25, 0.125, 0.375] {
+            g.push_history(&[Point { x: 0.0, y: *y }]);
+        }
+        g.render_control(&[Point { x: 0.0, y: 0.5 }], &readout());
+        let calls = calls(g.surface());
+        // 290 wide, 116 of which for the sparkline from 184 to 295.
+        assert!(calls.contains(&Call::FillText("5.00000".into(), 5.0, 16.0)));
+        assert!(calls.contains(&Call::MoveTo(184.0, 10.0)));
+        assert!(calls.contains(&Call::LineTo(239.5, 16.0)));
+        assert!(calls.contains(&Call::LineTo(295.0, 4.0)));
+        assert!(calls.contains(&Call::Arc(295.0, 4.0, 1.5, 0.0, PI * 2.0)));
+    }
+}
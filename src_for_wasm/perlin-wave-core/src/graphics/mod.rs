@@ -0,0 +1,43 @@
+pub mod control;
+pub mod wave;
+
+use std::any::Any;
+
+use crate::geometry;
+use crate::surface::Surface;
+use crate::theme::Theme;
+
+pub trait Graphics: Any {
+    fn as_any(&self) -> &dyn Any;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+
+    fn surface(&mut self) -> &mut dyn Surface;
+
+    fn size(&self) -> (f64, f64);
+
+    fn bgcolor(&self) -> &str;
+
+    /// Takes effect from the next frame.
+    fn set_theme(&mut self, theme: &Theme);
+
+    fn clear(&mut self) {
+        let (width, height) = self.size();
+        let bgcolor: String = self.bgcolor().into();
+        let surface = self.surface();
+        surface.clear();
+        surface.set_fill_style(bgcolor.as_str());
+        surface.fill_rect(0.0, 0.0, width, height);
+    }
+
+    fn reset(&mut self, _width: f64, _height: f64) {}
+
+    /// See `geometry::relative_pos_full`.
+    fn relative_pos_full(&self, elapsed: f64, cycle: f64) -> f64 {
+        geometry::relative_pos_full(elapsed, cycle)
+    }
+
+    /// See `geometry::relative_pos_half`.
+    fn relative_pos_half(&self, elapsed: f64, cycle: f64) -> f64 {
+        geometry::relative_pos_half(elapsed, cycle)
+    }
+}
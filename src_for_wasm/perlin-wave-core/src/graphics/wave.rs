@@ -0,0 +1,866 @@
+use std::any::Any;
+use std::f64::consts::PI;
+
+use crate::constants::SEGMENTS;
+use crate::easing::EasingConfig;
+use crate::geometry::{self, Rect, SolarBar, SolarInfo};
+use crate::graphics::Graphics;
+use crate::palette::{ColorConfig, GradientKind, Palette};
+use crate::spline::{self, CurveConfig, CurveType};
+use crate::surface::{Gradient, GradientShape, Surface};
+use crate::theme::Theme;
+use crate::timeline::Frame;
+use crate::transition::{Blend, TransitionKind};
+use crate::types::{GraphType, Point};
+
+pub struct WaveGraphics {
+    surface: Box<dyn Surface>,
+    pub width: f64,
+    pub height: f64,
+    solar_info: SolarInfo,
+    /// Its `secondary` and `font` are not used.
+    theme: Theme,
+    easing: EasingConfig,
+    /// For the lines of `GraphType::Radio`,
+    /// `GraphType::Area`, and `GraphType::Mirror`.
+    curve: CurveConfig,
+    colors: ColorConfig,
+    /// Without a palette, blended into from `primary` of the theme
+    /// as the value grows.
+    peak_color: Option<String>,
+}
+
+impl Graphics for WaveGraphics {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn surface(&mut self) -> &mut dyn Surface {
+        self.surface.as_mut()
+    }
+
+    fn size(&self) -> (f64, f64) {
+        (self.width, self.height)
+    }
+
+    fn bgcolor(&self) -> &str {
+        &self.theme.background
+    }
+
+    fn set_theme(&mut self, theme: &Theme) {
+        self.theme = theme.clone();
+    }
+
+    fn reset(&mut self, width: f64, height: f64) {
+        self.width = width;
+        self.height = height;
+        self.solar_info = SolarInfo::new(height, self.solar_info.segments);
+    }
+}
+
+impl WaveGraphics {
+    pub fn new(
+        surface: Box<dyn Surface>,
+        width: f64,
+        height: f64,
+        bgcolor: &str,
+        color: &str,
+    ) -> WaveGraphics {
+        // Made again in `render_solar` if the wave
+        // turns out to have a different number of segments.
+        let solar_info = SolarInfo::new(height, SEGMENTS);
+
+        WaveGraphics {
+            surface,
+            width,
+            height,
+            solar_info,
+            theme: Theme {
+                background: bgcolor.into(),
+                primary: color.into(),
+                ..Theme::default()
+            },
+            easing: EasingConfig::default(),
+            curve: CurveConfig::default(),
+            colors: ColorConfig::default(),
+            peak_color: None,
+        }
+    }
+
+    /// The same settings drawing on another surface.
+    pub fn with_surface(&self, surface: Box<dyn Surface>) -> WaveGraphics {
+        WaveGraphics {
+            surface,
+            width: self.width,
+            height: self.height,
+            solar_info: self.solar_info.clone(),
+            theme: self.theme.clone(),
+            easing: self.easing.clone(),
+            curve: self.curve.clone(),
+            colors: self.colors.clone(),
+            peak_color: self.peak_color.clone(),
+        }
+    }
+
+    pub fn color(&self) -> &str {
+        &self.theme.primary
+    }
+
+    pub fn set_easing(&mut self, easing: EasingConfig) {
+        self.easing = easing;
+    }
+
+    pub fn set_curve(&mut self, curve: CurveConfig) {
+        self.curve = curve;
+    }
+
+    pub fn set_colors(&mut self, colors: ColorConfig) {
+        self.colors = colors;
+    }
+
+    pub fn set_peak_color(&mut self, color: Option<String>) {
+        self.peak_color = color;
+    }
+
+    /// `colors.palette`, or the one from `primary` to `peak_color`.
+    fn palette(&self) -> Option<Palette> {
+        match (&self.colors.palette, &self.peak_color) {
+            (Some(palette), _) => Some(palette.clone()),
+            (None, Some(peak)) => Some(Palette::Stops(vec![
+                (0.0, self.theme.primary.clone()),
+                (1.0, peak.clone()),
+            ])),
+            (None, None) => None,
+        }
+    }
+
+    /// The color for `value` (`0.0` to `1.0`) in the palette,
+    /// or the single color when there is no palette.
+    fn color_at(&self, value: f64) -> String {
+        match self.palette() {
+            Some(palette) => palette.at(value),
+            None => self.theme.primary.clone(),
+        }
+    }
+
+    /// Groups `items` in a row of the same color,
+    /// so that each group is drawn at once.
+    fn by_color<T>(&self, items: Vec<T>, value: impl Fn(&T) -> f64) -> Vec<(String, Vec<T>)> {
+        let mut groups: Vec<(String, Vec<T>)> = vec![];
+        for item in items {
+            let color = self.color_at(value(&item));
+            match groups.last_mut() {
+                Some((last, group)) if *last == color => group.push(item),
+                _ => groups.push((color, vec![item])),
+            }
+        }
+        groups
+    }
+
+    /// The palette as a gradient for the lines,
+    /// or `None` when there is no palette.
+    fn line_gradient(&self) -> Option<Gradient> {
+        let stops = self.palette()?.stops();
+        let (half_w, half_h) = (self.width / 2.0, self.height / 2.0);
+        Some(match self.colors.gradient {
+            GradientKind::Linear => {
+                let amplify = geometry::amplify_value(self.height);
+                // The middle is for `0.0`, and both ends for `1.0`.
+                let upper = stops.iter().rev().map(|(o, c)| (0.5 - o / 2.0, c.clone()));
+                let lower = stops.iter().map(|(o, c)| (0.5 + o / 2.0, c.clone()));
+                Gradient {
+                    shape: GradientShape::Linear {
+                        x0: 0.0,
+                        y0: half_h - amplify,
+                        x1: 0.0,
+                        y1: half_h + amplify,
+                    },
+                    stops: upper.chain(lower).collect(),
+                }
+            }
+            GradientKind::Radial => Gradient {
+                shape: GradientShape::Radial {
+                    x: half_w,
+                    y: half_h,
+                    r0: 0.0,
+                    r1: half_w.max(half_h),
+                },
+                stops,
+            },
+        })
+    }
+
+    /// `save`s, and sets the line width and the glow of the theme.
+    fn save(ctx: &mut dyn Surface, theme: &Theme) {
+        ctx.save();
+        ctx.set_line_width(theme.line_width);
+        if theme.glow > 0.0 {
+            ctx.set_shadow(theme.glow, &theme.accent);
+        }
+    }
+
+    fn set_line_style(ctx: &mut dyn Surface, gradient: &Option<Gradient>, color: &str) {
+        match gradient {
+            Some(gradient) => ctx.set_stroke_gradient(gradient),
+            None => ctx.set_stroke_style(color),
+        }
+    }
+
+    pub fn render(
+        &mut self,
+        graph_type: GraphType,
+        points: &[Point],
+        points_prev: &[Point],
+        frame: Frame,
+    ) {
+        match graph_type {
+            GraphType::Radio => self.render_radio(points, frame),
+            GraphType::Bars => self.render_bars(points, points_prev, frame),
+            GraphType::Solar => self.render_solar(points, points_prev, frame),
+            GraphType::Area => self.render_area(points, frame),
+            GraphType::Mirror => self.render_mirror(points, frame),
+            GraphType::Dots => self.render_dots(points, points_prev, frame),
+            GraphType::Stems => self.render_stems(points, points_prev, frame),
+        }
+    }
+
+    /// Adds `path` to the current path, either
+    /// straight or curved depending on `curve`.
+    fn trace(ctx: &mut dyn Surface, path: &[(f64, f64)], curve: &CurveConfig) {
+        if let Some((x, y)) = path.first() {
+            ctx.move_to(*x, *y);
+        }
+        if curve.kind == CurveType::Linear {
+            for (x, y) in path.iter().skip(1) {
+                ctx.line_to(*x, *y);
+            }
+        } else {
+            for b in spline::curve(path, curve) {
+                ctx.bezier_curve_to(b.c1.0, b.c1.1, b.c2.0, b.c2.1, b.to.0, b.to.1);
+            }
+        }
+    }
+
+    fn radio_path(&self, points: &[Point], frame: Frame) -> Vec<(f64, f64)> {
+        let rel_pos: f64 = self
+            .easing
+            .radio
+            .apply(self.relative_pos_half(frame.elapsed, frame.cycle));
+        geometry::radio_path(points, self.width, self.height, rel_pos)
+    }
+
+    fn bars(&self, points: &[Point], points_prev: &[Point], frame: Frame) -> Vec<Rect> {
+        // Finding out where we are within the cycle.
+        // What we get is just a ratio between `0.0` and `1.0`.
+        // `0.0` being at the beginning of the cycle,
+        // and `1.0` being the end of the cycle.
+        let rel_pos: f64 = self
+            .easing
+            .bars
+            .apply(self.relative_pos_full(frame.elapsed, frame.cycle));
+        geometry::bars(points, points_prev, self.width, self.height, rel_pos)
+    }
+
+    fn solar_bars(
+        &mut self,
+        points: &[Point],
+        points_prev: &[Point],
+        frame: Frame,
+    ) -> Vec<SolarBar> {
+        if self.solar_info.segments != points.len() {
+            self.solar_info = SolarInfo::new(self.height, points.len());
+        }
+        let rel_pos: f64 = self
+            .easing
+            .solar
+            .apply(self.relative_pos_full(frame.elapsed, frame.cycle));
+        geometry::solar_bars(&self.solar_info, points, points_prev, rel_pos)
+    }
+
+    pub fn render_radio(&mut self, points: &[Point], frame: Frame) {
+        let path = self.radio_path(points, frame);
+        let gradient = self.line_gradient();
+
+        let ctx = self.surface.as_mut();
+        WaveGraphics::save(ctx, &self.theme);
+        WaveGraphics::set_line_style(ctx, &gradient, &self.theme.primary);
+        ctx.begin_path();
+
+        WaveGraphics::trace(ctx, &path, &self.curve);
+
+        ctx.stroke();
+        ctx.restore();
+    }
+
+    /// Same line as `render_radio`, but filled below
+    /// with the color fading into the background.
+    pub fn render_area(&mut self, points: &[Point], frame: Frame) {
+        let rel_pos: f64 = self
+            .easing
+            .area
+            .apply(self.relative_pos_half(frame.elapsed, frame.cycle));
+        let path = geometry::radio_path(points, self.width, self.height, rel_pos);
+        let top = self.height / 2.0 - geometry::amplify_value(self.height);
+        let gradient = self.line_gradient();
+
+        let ctx = self.surface.as_mut();
+        WaveGraphics::save(ctx, &self.theme);
+        ctx.set_fill_gradient(&Gradient {
+            shape: GradientShape::Linear {
+                x0: 0.0,
+                y0: top,
+                x1: 0.0,
+                y1: self.height,
+            },
+            stops: vec![
+                (0.0, self.theme.primary.clone()),
+                (1.0, self.theme.background.clone()),
+            ],
+        });
+        ctx.begin_path();
+        WaveGraphics::trace(ctx, &path, &self.curve);
+        ctx.line_to(self.width, self.height);
+        ctx.line_to(0.0, self.height);
+        ctx.close_path();
+        ctx.fill();
+
+        WaveGraphics::set_line_style(ctx, &gradient, &self.theme.primary);
+        ctx.begin_path();
+        WaveGraphics::trace(ctx, &path, &self.curve);
+        ctx.stroke();
+        ctx.restore();
+    }
+
+    /// Same line as `render_radio` together with its reflection.
+    pub fn render_mirror(&mut self, points: &[Point], frame: Frame) {
+        let rel_pos: f64 = self
+            .easing
+            .mirror
+            .apply(self.relative_pos_half(frame.elapsed, frame.cycle));
+        let path = geometry::radio_path(points, self.width, self.height, rel_pos);
+        let mirrored = geometry::mirror_path(&path, self.height);
+        let gradient = self.line_gradient();
+
+        let ctx = self.surface.as_mut();
+        WaveGraphics::save(ctx, &self.theme);
+        WaveGraphics::set_line_style(ctx, &gradient, &self.theme.primary);
+        ctx.begin_path();
+        WaveGraphics::trace(ctx, &path, &self.curve);
+        WaveGraphics::trace(ctx, &mirrored, &self.curve);
+        ctx.stroke();
+        ctx.restore();
+    }
+
+    pub fn render_dots(&mut self, points: &[Point], points_prev: &[Point], frame: Frame) {
+        let rel_pos: f64 = self
+            .easing
+            .dots
+            .apply(self.relative_pos_full(frame.elapsed, frame.cycle));
+        let dots = geometry::dots(points, points_prev, self.width, self.height, rel_pos);
+        let radius = geometry::dot_radius(self.width, points.len());
+        let (half_h, amplify) = (self.height / 2.0, geometry::amplify_value(self.height));
+        let groups = self.by_color(dots, |dot| (dot.y - half_h).abs() / amplify);
+
+        let ctx = self.surface.as_mut();
+        WaveGraphics::save(ctx, &self.theme);
+        for (color, dots) in groups {
+            ctx.set_fill_style(&color);
+            ctx.begin_path();
+            for dot in dots {
+                // Not to be joined to the previous dot.
+                ctx.move_to(dot.x + radius, dot.y);
+                ctx.arc(dot.x, dot.y, radius, 0.0, 2.0 * PI);
+            }
+            ctx.fill();
+        }
+        ctx.restore();
+    }
+
+    pub fn render_stems(&mut self, points: &[Point], points_prev: &[Point], frame: Frame) {
+        let rel_pos: f64 = self
+            .easing
+            .stems
+            .apply(self.relative_pos_full(frame.elapsed, frame.cycle));
+        let stems = geometry::stems(points, points_prev, self.width, self.height, rel_pos);
+        let radius = geometry::dot_radius(self.width, points.len());
+        let groups = self.by_color(stems, |stem| stem.value);
+
+        let ctx = self.surface.as_mut();
+        WaveGraphics::save(ctx, &self.theme);
+        for (color, stems) in groups {
+            ctx.set_stroke_style(&color);
+            ctx.begin_path();
+            for stem in stems.iter() {
+                ctx.move_to(stem.x, stem.base);
+                ctx.line_to(stem.x, stem.top);
+            }
+            ctx.stroke();
+
+            ctx.set_fill_style(&color);
+            ctx.begin_path();
+            for stem in stems.iter() {
+                ctx.move_to(stem.x + radius, stem.top);
+                ctx.arc(stem.x, stem.top, radius, 0.0, 2.0 * PI);
+            }
+            ctx.fill();
+        }
+        ctx.restore();
+    }
+
+    pub fn render_bars(&mut self, points: &[Point], points_prev: &[Point], frame: Frame) {
+        let bars = self.bars(points, points_prev, frame);
+        let amplify = geometry::amplify_value(self.height);
+        let groups = self.by_color(bars, |bar| bar.height / 2.0 / amplify);
+
+        let ctx = self.surface.as_mut();
+        WaveGraphics::save(ctx, &self.theme);
+
+        for (color, bars) in groups {
+            ctx.set_fill_style(&color);
+            for bar in bars {
+                ctx.fill_rect(bar.x, bar.y, bar.width, bar.height);
+            }
+        }
+        ctx.restore();
+    }
+
+    pub fn render_solar(&mut self, points: &[Point], points_prev: &[Point], frame: Frame) {
+        let offset_x = self.width / 2.0;
+        let offset_y = self.height / 2.0;
+        let bars = self.solar_bars(points, points_prev, frame);
+        let max_length = self.solar_info.max_length;
+        let groups = self.by_color(bars, |bar| bar.rect.width / max_length);
+
+        let ctx = self.surface.as_mut();
+        WaveGraphics::save(ctx, &self.theme);
+
+        // First, moving the canvas to the center.
+        ctx.translate(offset_x, offset_y);
+
+        for (color, bars) in groups {
+            ctx.set_fill_style(&color);
+            for bar in bars {
+                // Notice it `save` and `restore`
+                // the canvas translation every time.
+                ctx.save();
+                ctx.rotate(bar.angle);
+                ctx.fill_rect(bar.rect.x, bar.rect.y, bar.rect.width, bar.rect.height);
+                ctx.restore();
+            }
+        }
+        ctx.restore();
+    }
+
+    /// Either a single graph type as `render` does,
+    /// or two of them in the middle of a transition.
+    pub fn render_blend(
+        &mut self,
+        blend: Blend,
+        points: &[Point],
+        points_prev: &[Point],
+        frame: Frame,
+    ) {
+        let (from, to, kind, progress) = match blend {
+            Blend::Single(graph_type) => {
+                return self.render(graph_type, points, points_prev, frame);
+            }
+            Blend::Between {
+                from,
+                to,
+                kind,
+                progress,
+            } => (from, to, kind, progress),
+        };
+
+        use GraphType::*;
+        match (kind, from, to) {
+            (TransitionKind::Morph, Radio, Bars) => {
+                self.render_radio_to_bars(points, points_prev, frame, progress)
+            }
+            (TransitionKind::Morph, Bars, Radio) => {
+                self.render_radio_to_bars(points, points_prev, frame, 1.0 - progress)
+            }
+            (TransitionKind::Morph, Bars, Solar) => {
+                self.render_bars_to_solar(points, points_prev, frame, progress)
+            }
+            (TransitionKind::Morph, Solar, Bars) => {
+                self.render_bars_to_solar(points, points_prev, frame, 1.0 - progress)
+            }
+            _ => {
+                self.surface.save();
+                self.surface.set_global_alpha(1.0 - progress);
+                self.render(from, points, points_prev, frame);
+                self.surface.restore();
+
+                self.surface.save();
+                self.surface.set_global_alpha(progress);
+                self.render(to, points, points_prev, frame);
+                self.surface.restore();
+            }
+        }
+    }
+
+    /// The line fading out while the bars grow out of it.
+    fn render_radio_to_bars(
+        &mut self,
+        points: &[Point],
+        points_prev: &[Point],
+        frame: Frame,
+        progress: f64,
+    ) {
+        let path = self.radio_path(points, frame);
+        let bars = self.bars(points, points_prev, frame);
+        let rects = geometry::morph_line_to_bars(&path, &bars, progress);
+        let amplify = geometry::amplify_value(self.height);
+        let groups = self.by_color(rects, |rect| rect.height / 2.0 / amplify);
+        let gradient = self.line_gradient();
+
+        let ctx = self.surface.as_mut();
+        WaveGraphics::save(ctx, &self.theme);
+        ctx.set_global_alpha(1.0 - progress);
+        WaveGraphics::set_line_style(ctx, &gradient, &self.theme.primary);
+        ctx.begin_path();
+        WaveGraphics::trace(ctx, &path, &self.curve);
+        ctx.stroke();
+
+        ctx.set_global_alpha(1.0);
+        for (color, rects) in groups {
+            ctx.set_fill_style(&color);
+            for rect in rects {
+                ctx.fill_rect(rect.x, rect.y, rect.width, rect.height);
+            }
+        }
+        ctx.restore();
+    }
+
+    /// The bars folding into the ring, while those
+    /// which have no place in the ring fade out.
+    fn render_bars_to_solar(
+        &mut self,
+        points: &[Point],
+        points_prev: &[Point],
+        frame: Frame,
+        progress: f64,
+    ) {
+        let bars = self.bars(points, points_prev, frame);
+        let solar = self.solar_bars(points, points_prev, frame);
+        let center = (self.width / 2.0, self.height / 2.0);
+        let placed = geometry::morph_bars_to_solar(&bars, &solar, center, progress);
+        let amplify = geometry::amplify_value(self.height);
+        let max_length = self.solar_info.max_length;
+        // Colored as `render_bars` does, and then as `render_solar` does.
+        let placed: Vec<_> = placed
+            .into_iter()
+            .zip(bars.iter().zip(solar.iter()))
+            .map(|(p, (bar, sol))| {
+                let from = bar.height / 2.0 / amplify;
+                (p, from + (sol.rect.width / max_length - from) * progress)
+            })
+            .collect();
+        let left = placed.len();
+        let placed = self.by_color(placed, |(_, value)| *value);
+        let rest = self.by_color(bars.into_iter().skip(left).collect(), |bar| {
+            bar.height / 2.0 / amplify
+        });
+
+        let ctx = self.surface.as_mut();
+        WaveGraphics::save(ctx, &self.theme);
+        for (color, placed) in placed {
+            ctx.set_fill_style(&color);
+            for (p, _) in placed.iter() {
+                ctx.save();
+                ctx.translate(p.x, p.y);
+                ctx.rotate(p.angle);
+                ctx.fill_rect(p.rect.x, p.rect.y, p.rect.width, p.rect.height);
+                ctx.restore();
+            }
+        }
+
+        ctx.set_global_alpha(1.0 - progress);
+        for (color, bars) in rest {
+            ctx.set_fill_style(&color);
+            for bar in bars {
+                ctx.fill_rect(bar.x, bar.y, bar.width, bar.height);
+            }
+        }
+        ctx.restore();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::surface::mock::{calls, Call, Recorder};
+
+    fn graphics() -> WaveGraphics {
+        WaveGraphics::new(Box::new(Recorder::default()), 550.0, 200.0, "#222", "#fff")
+    }
+
+    fn points(y: f64) -> Vec<Point> {
+        vec![Point { x: 0.0, y }; SEGMENTS]
+    }
+
+    #[test]
+    fn clear_paints_the_background() {
+        let mut g = graphics();
+        g.clear();
+        assert_eq!(
+            calls(g.surface()),
+            vec![
+                Call::Clear,
+                Call::FillStyle("#222".into()),
+                Call::FillRect(0.0, 0.0, 550.0, 200.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn render_radio_strokes_a_single_path() {
+        let mut g = graphics();
+        g.render_radio(&points(0.5), Frame::default());
+        let calls = calls(g.surface());
+        let lines = calls
+            .iter()
+            .filter(|c| matches!(c, Call::LineTo(..)))
+            .count();
+        assert_eq!(lines, SEGMENTS + 1);
+        assert_eq!(calls.iter().filter(|c| **c == Call::Stroke).count(), 1);
+    }
+
+    #[test]
+    fn render_radio_as_a_curve() {
+        let mut g = graphics();
+        g.set_curve(CurveConfig {
+            kind: CurveType::CatmullRom,
+            tension: 0.5,
+        });
+        g.render_radio(&points(0.5), Frame::default());
+        let calls = calls(g.surface());
+        assert!(!calls.iter().any(|c| matches!(c, Call::LineTo(..))));
+        let curves = calls
+            .iter()
+            .filter(|c| matches!(c, Call::BezierCurveTo(..)))
+            .count();
+        assert_eq!(curves, SEGMENTS + 1);
+    }
+
+    #[test]
+    fn every_graph_type_draws_something() {
+        for graph_type in GraphType::ALL.iter() {
+            let mut g = graphics();
+            g.render(
+                *graph_type,
+                &points(0.5),
+                &points(0.0),
+                Frame::middle(350.0),
+            );
+            let calls = calls(g.surface());
+            assert!(
+                calls
+                    .iter()
+                    .any(|c| matches!(c, Call::Stroke | Call::Fill | Call::FillRect(..))),
+                "{:?}",
+                graph_type
+            );
+            let saves = calls.iter().filter(|c| **c == Call::Save).count();
+            let restores = calls.iter().filter(|c| **c == Call::Restore).count();
+            assert_eq!(saves, restores, "{:?}", graph_type);
+        }
+    }
+
+    #[test]
+    fn themed_lines_glow() {
+        let mut g = graphics();
+        g.set_theme(&Theme::high_contrast());
+        g.render_radio(&points(0.5), Frame::middle(350.0));
+        let calls = calls(g.surface());
+        let theme = Theme::high_contrast();
+        assert!(calls.contains(&Call::StrokeStyle(theme.primary)));
+        assert!(calls.contains(&Call::LineWidth(2.0)));
+        assert!(calls.contains(&Call::Shadow(4.0, theme.accent)));
+
+        g.set_theme(&Theme::dark());
+        g.render_radio(&points(0.5), Frame::middle(350.0));
+        let calls = super::tests::calls(g.surface());
+        assert!(!calls.iter().any(|c| matches!(c, Call::Shadow(..))));
+    }
+
+    #[test]
+    fn render_area_fills_with_a_gradient() {
+        let mut g = graphics();
+        g.render_area(&points(0.5), Frame::middle(350.0));
+        let calls = calls(g.surface());
+        assert!(calls.iter().any(|c| matches!(
+            c,
+            Call::FillGradient(gradient) if gradient.stops[1].1 == "#222"
+        )));
+        assert_eq!(calls.iter().filter(|c| **c == Call::Fill).count(), 1);
+    }
+
+    #[test]
+    fn fade_draws_both_with_alpha() {
+        let mut g = graphics();
+        g.render_blend(
+            Blend::Between {
+                from: GraphType::Radio,
+                to: GraphType::Dots,
+                kind: TransitionKind::Fade,
+                progress: 0.25,
+            },
+            &points(0.5),
+            &points(0.0),
+            Frame::middle(350.0),
+        );
+        let calls = calls(g.surface());
+        assert!(calls.contains(&Call::GlobalAlpha(0.75)));
+        assert!(calls.contains(&Call::GlobalAlpha(0.25)));
+        assert!(calls.contains(&Call::Stroke));
+        assert!(calls.contains(&Call::Fill));
+    }
+
+    #[test]
+    fn morph_to_solar_places_every_ring_bar() {
+        let mut g = graphics();
+        g.render_blend(
+            Blend::Between {
+                from: GraphType::Solar,
+                to: GraphType::Bars,
+                kind: TransitionKind::Morph,
+                progress: 0.5,
+            },
+            &points(0.5),
+            &points(0.0),
+            Frame::middle(350.0),
+        );
+        let calls = calls(g.surface());
+        let rotations = calls
+            .iter()
+            .filter(|c| matches!(c, Call::Rotate(_)))
+            .count();
+        assert_eq!(rotations, 44);
+        let rects = calls
+            .iter()
+            .filter(|c| matches!(c, Call::FillRect(..)))
+            .count();
+        assert_eq!(rects, SEGMENTS);
+    }
+
+    #[test]
+    fn palette_colors_each_bar() {
+        use crate::palette::{Palette, PaletteName};
+
+        let mut g = graphics();
+        g.set_colors(ColorConfig {
+            palette: Some(Palette::Named(PaletteName::Viridis)),
+            ..ColorConfig::default()
+        });
+        let tall: Vec<Point> = (0..SEGMENTS)
+            .map(|i| Point {
+                x: 0.0,
+                y: (i as f64 / SEGMENTS as f64) * 2.0 - 1.0,
+            })
+            .collect();
+        g.render_bars(&tall, &tall, Frame::middle(350.0));
+        let calls = calls(g.surface());
+        let styles = calls
+            .iter()
+            .filter(|c| matches!(c, Call::FillStyle(_)))
+            .count();
+        assert!(styles > 10);
+        assert!(calls.contains(&Call::FillStyle("#fde725".into())));
+
+        g.render_radio(&tall, Frame::middle(350.0));
+        let calls = super::tests::calls(g.surface());
+        assert!(calls
+            .iter()
+            .any(|c| matches!(c, Call::StrokeGradient(gradient) if gradient.stops.len() == 20)));
+    }
+
+    #[test]
+    fn morphs_keep_the_palette() {
+        use crate::palette::{Palette, PaletteName};
+
+        for (from, to) in [
+            (GraphType::Radio, GraphType::Bars),
+            (GraphType::Bars, GraphType::Solar),
+        ]
+        .iter()
+        {
+            let mut g = graphics();
+            g.set_colors(ColorConfig {
+                palette: Some(Palette::Named(PaletteName::Viridis)),
+                ..ColorConfig::default()
+            });
+            g.render_blend(
+                Blend::Between {
+                    from: *from,
+                    to: *to,
+                    kind: TransitionKind::Morph,
+                    progress: 0.5,
+                },
+                &points(0.5),
+                &points(0.5),
+                Frame::middle(350.0),
+            );
+            let calls = calls(g.surface());
+            assert!(!calls.contains(&Call::FillStyle("#fff".into())), "{:?}", to);
+            assert!(calls
+                .iter()
+                .any(|c| matches!(c, Call::FillStyle(color) if color.starts_with('#'))));
+        }
+    }
+
+    #[test]
+    fn blended_into_the_peak_color() {
+        let mut g = graphics();
+        g.set_peak_color(Some("#000".into()));
+        let tall: Vec<Point> = (0..SEGMENTS)
+            .map(|i| Point {
+                x: 0.0,
+                y: i as f64 / (SEGMENTS - 1) as f64,
+            })
+            .collect();
+        g.render_bars(&tall, &tall, Frame::middle(350.0));
+        let calls = calls(g.surface());
+        assert!(calls.contains(&Call::FillStyle("#ffffff".into())));
+        assert!(calls.contains(&Call::FillStyle("#000000".into())));
+    }
+
+    #[test]
+    fn render_solar_balances_save_and_restore() {
+        let mut g = graphics();
+        g.render_solar(&points(0.5), &points(0.0), Frame::middle(350.0));
+        let calls = calls(g.surface());
+        let saves = calls.iter().filter(|c| **c == Call::Save).count();
+        let restores = calls.iter().filter(|c| **c == Call::Restore).count();
+        assert_eq!(saves, restores);
+        assert_eq!(
+            calls
+                .iter()
+                .filter(|c| matches!(c, Call::Rotate(_)))
+                .count(),
+            44
+        );
+    }
+
+    #[test]
+    fn solar_fits_the_size_after_reset() {
+        let mut g = graphics();
+        g.reset(300.0, 100.0);
+        g.render_solar(&points(1.0), &points(1.0), Frame::middle(350.0));
+        let calls = calls(g.surface());
+        assert!(calls.contains(&Call::Translate(150.0, 50.0)));
+        let longest = calls
+            .iter()
+            .filter_map(|c| match c {
+                Call::FillRect(x, _, w, _) => Some(x + w),
+                _ => None,
+            })
+            .fold(0.0, f64::max);
+        assert!(longest <= 50.0, "{}", longest);
+    }
+}
@@ -0,0 +1,79 @@
+//! Values of the past cycles for the sparkline of the control panel.
+
+use std::collections::VecDeque;
+
+/// Keeps the last `capacity` values, dropping the oldest.
+#[derive(Clone, Debug, PartialEq)]
+pub struct History {
+    values: VecDeque<f64>,
+    capacity: usize,
+}
+
+impl History {
+    /// Keeps nothing when `capacity` is `0`.
+    /// Grows as the values are pushed.
+    pub fn new(capacity: usize) -> History {
+        History {
+            values: VecDeque::new(),
+            capacity,
+        }
+    }
+
+    pub fn push(&mut self, value: f64) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.values.len() == self.capacity {
+            self.values.pop_front();
+        }
+        self.values.push_back(value);
+    }
+
+    /// From the oldest to the latest.
+    pub fn values(&self) -> impl Iterator<Item = &f64> {
+        self.values.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// The smallest and the largest value for the sparkline to scale to.
+    pub fn range(&self) -> Option<(f64, f64)> {
+        self.values.iter().fold(None, |range, v| match range {
+            None => Some((*v, *v)),
+            Some((min, max)) => Some((min.min(*v), max.max(*v))),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn drops_the_oldest() {
+        let mut history = History::new(3);
+        assert_eq!(history.range(), None);
+        for v in &[4.0, 1.0, 3.0, 2.0] {
+            history.push(*v);
+        }
+        assert_eq!(
+            history.values().copied().collect::<Vec<_>>(),
+            [1.0, 3.0, 2.0]
+        );
+        assert_eq!(history.range(), Some((1.0, 3.0)));
+
+        let mut none = History::new(0);
+        none.push(1.0);
+        assert!(none.is_empty());
+    }
+}
@@ -0,0 +1,226 @@
+//! The size of a panel in its wrapper element.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum FitMode {
+    /// As large as fits in the wrapper, keeping the ratio.
+    #[default]
+    Contain,
+    /// As small as covers the wrapper, keeping the ratio.
+    Cover,
+    /// `width` and `height` as they are.
+    Fixed,
+}
+
+/// What the panel specs give about the size.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Layout {
+    pub width: Option<f64>,
+    pub height: Option<f64>,
+    /// Width over height.
+    pub ratio: Option<f64>,
+    pub min_height: Option<f64>,
+    pub max_height: Option<f64>,
+    pub fit: FitMode,
+}
+
+impl Layout {
+    /// `ratio`, or the one of `width` and `height`.
+    pub fn ratio(&self) -> Option<f64> {
+        match (self.ratio, self.width, self.height) {
+            (Some(ratio), _, _) => Some(ratio),
+            (None, Some(width), Some(height)) => Some(width / height),
+            _ => None,
+        }
+    }
+
+    /// Size of the panel in the wrapper of the size in CSS pixels.
+    /// `wrapper_height` is `0.0` when the wrapper has no height
+    /// of its own, and the width alone decides.
+    /// `min_height` and `max_height` come before the ratio.
+    pub fn size(&self, wrapper_width: f64, wrapper_height: f64) -> (f64, f64) {
+        let ratio = match (self.fit, self.ratio()) {
+            (FitMode::Fixed, _) | (_, None) => {
+                return (
+                    self.width.unwrap_or(wrapper_width),
+                    self.height.unwrap_or(wrapper_height),
+                );
+            }
+            (_, Some(ratio)) => ratio,
+        };
+        let width = if wrapper_height <= 0.0 {
+            wrapper_width
+        } else if self.fit == FitMode::Cover {
+            wrapper_width.max(wrapper_height * ratio)
+        } else {
+            wrapper_width.min(wrapper_height * ratio)
+        };
+        let mut height = width / ratio;
+        if let Some(max) = self.max_height {
+            height = height.min(max);
+        }
+        if let Some(min) = self.min_height {
+            height = height.max(min);
+        }
+        (width, (height * 10.0).round() / 10.0)
+    }
+}
+
+/// Inline styles of the wrapper element.
+pub trait Style {
+    /// `""` when not set.
+    fn get(&self, name: &str) -> String;
+    /// Removes it when `value` is `""`.
+    fn set(&mut self, name: &str, value: &str);
+}
+
+/// What the panel does to the inline styles of its wrapper,
+/// which `restore` undoes so that the next run measures
+/// the wrapper as it was in the first place.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Wrapper {
+    layout: Layout,
+    /// The wrapper has no height of its own,
+    /// and is given the height of the panel.
+    auto: bool,
+    /// Names and values of the styles before they were changed.
+    saved: Vec<(&'static str, String)>,
+}
+
+impl Wrapper {
+    /// `client_height` is measured before anything is changed.
+    pub fn new(style: &mut dyn Style, layout: Layout, client_height: f64) -> Wrapper {
+        let auto = client_height <= 0.0;
+        let mut saved = vec![];
+        if auto {
+            saved.push(("height", style.get("height")));
+        }
+        if layout.fit == FitMode::Cover {
+            saved.push(("overflow", style.get("overflow")));
+            style.set("overflow", "hidden");
+        }
+        Wrapper {
+            layout,
+            auto,
+            saved,
+        }
+    }
+
+    pub fn auto(&self) -> bool {
+        self.auto
+    }
+
+    /// `Layout::size` in the wrapper of the size, which is given
+    /// the height of the panel when it has none of its own.
+    /// Otherwise, the canvas produces a weird gap underneath.
+    pub fn fit(&self, style: &mut dyn Style, width: f64, height: f64) -> (f64, f64) {
+        let (width, height) = self
+            .layout
+            .size(width, if self.auto { 0.0 } else { height });
+        if self.auto {
+            style.set("height", &format!("{}px", height));
+        }
+        (width, height)
+    }
+
+    pub fn restore(&self, style: &mut dyn Style) {
+        for (name, value) in &self.saved {
+            style.set(name, value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    impl Style for HashMap<String, String> {
+        fn get(&self, name: &str) -> String {
+            self.get(name).cloned().unwrap_or_default()
+        }
+
+        fn set(&mut self, name: &str, value: &str) {
+            if value.is_empty() {
+                self.remove(name);
+            } else {
+                self.insert(name.into(), value.into());
+            }
+        }
+    }
+
+    fn layout(fit: FitMode) -> Layout {
+        Layout {
+            ratio: Some(3.0),
+            fit,
+            ..Layout::default()
+        }
+    }
+
+    #[test]
+    fn by_the_width_alone() {
+        assert_eq!(layout(FitMode::Contain).size(550.0, 0.0), (550.0, 183.3));
+        let clamped = Layout {
+            min_height: Some(200.0),
+            ..layout(FitMode::Contain)
+        };
+        assert_eq!(clamped.size(550.0, 0.0), (550.0, 200.0));
+        let clamped = Layout {
+            max_height: Some(100.0),
+            ..layout(FitMode::Cover)
+        };
+        assert_eq!(clamped.size(550.0, 0.0), (550.0, 100.0));
+    }
+
+    #[test]
+    fn in_the_box() {
+        assert_eq!(layout(FitMode::Contain).size(600.0, 100.0), (300.0, 100.0));
+        assert_eq!(layout(FitMode::Cover).size(600.0, 100.0), (600.0, 200.0));
+
+        let fixed = Layout {
+            width: Some(120.0),
+            height: Some(40.0),
+            fit: FitMode::Fixed,
+            ..Layout::default()
+        };
+        assert_eq!(fixed.size(600.0, 100.0), (120.0, 40.0));
+        // The ratio of the size given.
+        let contain = Layout {
+            fit: FitMode::Contain,
+            ..fixed
+        };
+        assert_eq!(contain.size(600.0, 0.0), (600.0, 200.0));
+    }
+
+    #[test]
+    fn wrapper_restored_for_the_next_run() {
+        let layout = Layout {
+            max_height: Some(150.0),
+            ..layout(FitMode::Cover)
+        };
+        let mut style: HashMap<String, String> = HashMap::new();
+        style.insert("overflow".into(), "auto".into());
+        // Without the inline height, the wrapper has none.
+        let client_height = |style: &HashMap<String, String>| {
+            if style.contains_key("height") {
+                150.0
+            } else {
+                0.0
+            }
+        };
+
+        for _ in 0..2 {
+            let measured = client_height(&style);
+            let wrapper = Wrapper::new(&mut style, layout.clone(), measured);
+            assert!(wrapper.auto());
+            assert_eq!(wrapper.fit(&mut style, 900.0, 0.0), (900.0, 150.0));
+            assert_eq!(Style::get(&style, "height"), "150px");
+            assert_eq!(Style::get(&style, "overflow"), "hidden");
+            wrapper.restore(&mut style);
+            assert_eq!(style.get("overflow").map(String::as_str), Some("auto"));
+            assert!(!style.contains_key("height"));
+        }
+    }
+}
@@ -0,0 +1,26 @@
+//! Everything about the waves which does not need a browser.
+//! Generates the points, and calculates where to draw them.
+//! `perlin-wave` is the web front-end drawing them on canvas.
+
+pub mod color;
+pub mod config;
+pub mod constants;
+pub mod controls;
+pub mod easing;
+pub mod fractal;
+pub mod geometry;
+pub mod graphics;
+pub mod history;
+pub mod layout;
+pub mod palette;
+pub mod perlin;
+pub mod rotation;
+pub mod spline;
+pub mod stats;
+pub mod surface;
+pub mod svg;
+pub mod theme;
+pub mod timeline;
+pub mod transition;
+pub mod types;
+pub mod wave;
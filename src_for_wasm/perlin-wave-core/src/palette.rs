@@ -0,0 +1,184 @@
+//! Colors chosen by value, so that bars are colored
+//! by how tall they are instead of in a single color.
+
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+use crate::color::Rgba;
+
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum PaletteName {
+    Viridis,
+    Magma,
+    Plasma,
+    Inferno,
+}
+
+/// Sampled from the colormaps of matplotlib.
+const VIRIDIS: [&str; 10] = [
+    "#440154", "#482878", "#3e4989", "#31688e", "#26828e", "#1f9e89", "#35b779", "#6ece58",
+    "#b5de2b", "#fde725",
+];
+const MAGMA: [&str; 10] = [
+    "#000004", "#180f3d", "#440f76", "#721f81", "#9e2f7f", "#cd4071", "#f1605d", "#fd9668",
+    "#feca8d", "#fcfdbf",
+];
+const PLASMA: [&str; 10] = [
+    "#0d0887", "#46039f", "#7201a8", "#9c179e", "#bd3786", "#d8576b", "#ed7953", "#fb9f3a",
+    "#fdca26", "#f0f921",
+];
+const INFERNO: [&str; 10] = [
+    "#000004", "#1b0c41", "#4a0c6b", "#781c6d", "#a52c60", "#cf4446", "#ed6925", "#fb9b06",
+    "#f7d13d", "#fcffa4",
+];
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(untagged)]
+pub enum Palette {
+    Named(PaletteName),
+    /// Pairs of an offset (`0.0` to `1.0`) and a color,
+    /// such as `[[0, "#000"], [1, "#c0e822"]]`.
+    Stops(Vec<(f64, String)>),
+}
+
+impl Palette {
+    pub fn stops(&self) -> Vec<(f64, String)> {
+        let colors = match self {
+            Palette::Stops(stops) => return stops.clone(),
+            Palette::Named(PaletteName::Viridis) => VIRIDIS,
+            Palette::Named(PaletteName::Magma) => MAGMA,
+            Palette::Named(PaletteName::Plasma) => PLASMA,
+            Palette::Named(PaletteName::Inferno) => INFERNO,
+        };
+        let last = (colors.len() - 1) as f64;
+        colors
+            .iter()
+            .enumerate()
+            .map(|(i, c)| (i as f64 / last, c.to_string()))
+            .collect()
+    }
+
+    /// The color for `value` between `0.0` and `1.0`.
+    pub fn at(&self, value: f64) -> String {
+        let stops: Vec<(f64, Rgba)> = self
+            .stops()
+            .iter()
+            .filter_map(|(offset, c)| Some((*offset, Rgba::parse(c)?)))
+            .collect();
+        let value = value.clamp(0.0, 1.0);
+        let color = match stops.iter().position(|(offset, _)| *offset >= value) {
+            None => stops.last().map(|(_, c)| *c),
+            Some(0) => stops.first().map(|(_, c)| *c),
+            Some(i) => {
+                let ((o0, c0), (o1, c1)) = (stops[i - 1], stops[i]);
+                let t = if o1 > o0 {
+                    (value - o0) / (o1 - o0)
+                } else {
+                    1.0
+                };
+                Some(c0.mix(&c1, t))
+            }
+        };
+        color.unwrap_or(Rgba::BLACK).to_string()
+    }
+
+    /// What is wrong with the stops, if any.
+    pub fn validate(&self) -> Result<(), String> {
+        let stops = match self {
+            Palette::Named(_) => return Ok(()),
+            Palette::Stops(stops) => stops,
+        };
+        if stops.is_empty() {
+            return Err("must have at least one stop".into());
+        }
+        let mut last = 0.0;
+        for (offset, color) in stops {
+            if !(last..=1.0).contains(offset) {
+                return Err(format!(
+                    "offsets must be in order between 0 and 1, got {}",
+                    offset
+                ));
+            }
+            last = *offset;
+            // Named colors can't be mixed without the browser.
+            if Rgba::parse(color).is_none() {
+                return Err(format!("invalid color (hex or rgb only): {:?}", color));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Palette {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "viridis" => Ok(Palette::Named(PaletteName::Viridis)),
+            "magma" => Ok(Palette::Named(PaletteName::Magma)),
+            "plasma" => Ok(Palette::Named(PaletteName::Plasma)),
+            "inferno" => Ok(Palette::Named(PaletteName::Inferno)),
+            _ => Err(format!("No such palette: {}", name)),
+        }
+    }
+}
+
+/// How the lines are colored with the palette.
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum GradientKind {
+    /// By the height from the middle.
+    #[default]
+    Linear,
+    /// By the distance from the center.
+    Radial,
+}
+
+impl FromStr for GradientKind {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "linear" => Ok(GradientKind::Linear),
+            "radial" => Ok(GradientKind::Radial),
+            _ => Err(format!("No such gradient: {}", name)),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct ColorConfig {
+    /// Draws in a single color when `None`.
+    pub palette: Option<Palette>,
+    /// For the lines of `GraphType::Radio`, `Area`, and `Mirror`.
+    pub gradient: GradientKind,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn picks_and_mixes() {
+        let palette = Palette::Stops(vec![(0.0, "#000".into()), (1.0, "#fff".into())]);
+        assert_eq!(palette.at(0.0), "#000000");
+        assert_eq!(palette.at(0.5), "#808080");
+        assert_eq!(palette.at(2.0), "#ffffff");
+
+        let viridis = Palette::Named(PaletteName::Viridis);
+        assert_eq!(viridis.at(0.0), VIRIDIS[0]);
+        assert_eq!(viridis.at(1.0), VIRIDIS[9]);
+    }
+
+    #[test]
+    fn named_or_stops() {
+        let named: Palette = serde_json::from_str(r#""magma""#).unwrap();
+        assert_eq!(named, Palette::Named(PaletteName::Magma));
+        let stops: Palette = serde_json::from_str(r##"[[0, "#000"], [1, "red"]]"##).unwrap();
+        assert!(stops.validate().unwrap_err().contains("red"));
+        let unordered = Palette::Stops(vec![(0.5, "#000".into()), (0.2, "#fff".into())]);
+        assert!(unordered.validate().is_err());
+    }
+}
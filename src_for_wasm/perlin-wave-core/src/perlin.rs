@@ -0,0 +1,106 @@
+use noise::{NoiseFn, OpenSimplex, Perlin, Simplex, SuperSimplex, Value, Worley};
+use serde::{Deserialize, Serialize};
+
+/// Anything which gives us a 2D noise value.
+/// `App` only talks to the noise through this trait
+/// so that the generator can be swapped (or mocked).
+pub trait NoiseSource {
+    fn get(&self, x: f64, y: f64) -> f64;
+    fn seed(&self) -> u32;
+    fn algorithm(&self) -> NoiseAlgorithm;
+}
+
+/// Noise generators we can choose from (`noise` in `Config`).
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum NoiseAlgorithm {
+    Perlin,
+    Simplex,
+    SuperSimplex,
+    Value,
+    Worley,
+    #[default]
+    OpenSimplex,
+}
+
+impl NoiseAlgorithm {
+    pub const ALL: [NoiseAlgorithm; 6] = [
+        NoiseAlgorithm::Perlin,
+        NoiseAlgorithm::Simplex,
+        NoiseAlgorithm::SuperSimplex,
+        NoiseAlgorithm::Value,
+        NoiseAlgorithm::Worley,
+        NoiseAlgorithm::OpenSimplex,
+    ];
+}
+
+/// One of the generators in `NoiseAlgorithm` with a fixed seed.
+/// As long as the seed is the same,
+/// it always returns the same value for the same input.
+pub struct SeededNoise {
+    noise: Box<dyn NoiseFn<f64, 2>>,
+    algorithm: NoiseAlgorithm,
+    seed: u32,
+}
+
+impl SeededNoise {
+    pub fn new(algorithm: NoiseAlgorithm, seed: u32) -> SeededNoise {
+        let noise: Box<dyn NoiseFn<f64, 2>> = match algorithm {
+            NoiseAlgorithm::Perlin => Box::new(Perlin::new(seed)),
+            NoiseAlgorithm::Simplex => Box::new(Simplex::new(seed)),
+            NoiseAlgorithm::SuperSimplex => Box::new(SuperSimplex::new(seed)),
+            NoiseAlgorithm::Value => Box::new(Value::new(seed)),
+            NoiseAlgorithm::Worley => Box::new(Worley::new(seed)),
+            NoiseAlgorithm::OpenSimplex => Box::new(OpenSimplex::new(seed)),
+        };
+
+        SeededNoise {
+            noise,
+            algorithm,
+            seed,
+        }
+    }
+
+    /// The generators take 32 bits, so the upper half
+    /// is folded into the lower instead of being dropped.
+    pub fn from_u64(algorithm: NoiseAlgorithm, seed: u64) -> SeededNoise {
+        SeededNoise::new(algorithm, (seed ^ (seed >> 32)) as u32)
+    }
+}
+
+impl NoiseSource for SeededNoise {
+    fn get(&self, x: f64, y: f64) -> f64 {
+        self.noise.get([x, y])
+    }
+
+    fn seed(&self) -> u32 {
+        self.seed
+    }
+
+    fn algorithm(&self) -> NoiseAlgorithm {
+        self.algorithm
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_seed_same_value() {
+        for algorithm in NoiseAlgorithm::ALL.iter() {
+            let a = SeededNoise::new(*algorithm, 42);
+            let b = SeededNoise::new(*algorithm, 42);
+            assert_eq!(a.get(1.3, 2.7), b.get(1.3, 2.7));
+        }
+    }
+
+    #[test]
+    fn different_seed_different_value() {
+        for algorithm in NoiseAlgorithm::ALL.iter() {
+            let a = SeededNoise::new(*algorithm, 1);
+            let b = SeededNoise::new(*algorithm, 2);
+            assert_ne!(a.get(1.3, 2.7), b.get(1.3, 2.7), "{:?}", algorithm);
+        }
+    }
+}
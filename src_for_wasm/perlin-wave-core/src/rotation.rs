@@ -0,0 +1,103 @@
+//! Which `GraphType` the wave panel shows,
+//! and which one comes next.
+
+use crate::types::GraphType;
+
+pub struct Rotation {
+    /// Never empty.
+    order: Vec<GraphType>,
+    current: GraphType,
+    /// Rotates by itself every this many cycles.
+    every: Option<u32>,
+    /// Cycles shown since the last change.
+    cycles: u32,
+}
+
+impl Rotation {
+    /// Goes through all of `GraphType` when `order` is empty.
+    pub fn new(current: GraphType, order: &[GraphType], every: Option<u32>) -> Rotation {
+        Rotation {
+            order: if order.is_empty() {
+                GraphType::ALL.to_vec()
+            } else {
+                order.to_vec()
+            },
+            current,
+            every: every.filter(|n| *n > 0),
+            cycles: 0,
+        }
+    }
+
+    pub fn current(&self) -> GraphType {
+        self.current
+    }
+
+    /// The one after `current` in the order, or the first
+    /// one when `current` is not in the order.
+    pub fn toggle(&mut self) -> GraphType {
+        let i = self
+            .order
+            .iter()
+            .position(|g| *g == self.current)
+            .map_or(0, |i| (i + 1) % self.order.len());
+        self.set(self.order[i]);
+        self.current
+    }
+
+    /// Shows `graph_type` for the full `every` cycles
+    /// before rotating again.
+    pub fn set(&mut self, graph_type: GraphType) {
+        self.current = graph_type;
+        self.cycles = 0;
+    }
+
+    /// To be called whenever a cycle begins.
+    /// Returns `true` when it has rotated.
+    pub fn cycle(&mut self) -> bool {
+        self.cycles += 1;
+        match self.every {
+            Some(every) if self.cycles > every => {
+                self.toggle();
+                // This cycle is the first for the new one.
+                self.cycles = 1;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn toggle_follows_the_order() {
+        let mut r = Rotation::new(GraphType::Solar, &[GraphType::Solar, GraphType::Dots], None);
+        assert_eq!(r.toggle(), GraphType::Dots);
+        assert_eq!(r.toggle(), GraphType::Solar);
+
+        r.set(GraphType::Bars);
+        assert_eq!(r.toggle(), GraphType::Solar);
+    }
+
+    #[test]
+    fn rotates_every_n_cycles() {
+        let mut r = Rotation::new(GraphType::Radio, &[], Some(2));
+        let shown: Vec<GraphType> = (0..7)
+            .map(|_| {
+                r.cycle();
+                r.current()
+            })
+            .collect();
+        use GraphType::*;
+        assert_eq!(shown, vec![Radio, Radio, Bars, Bars, Solar, Solar, Area]);
+    }
+
+    #[test]
+    fn never_rotates_without_every() {
+        let mut r = Rotation::new(GraphType::Radio, &[], None);
+        assert!(!(0..10).any(|_| r.cycle()));
+        assert_eq!(r.current(), GraphType::Radio);
+    }
+}
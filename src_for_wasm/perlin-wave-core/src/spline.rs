@@ -0,0 +1,377 @@
+//! Smooth curves through the vertices of `GraphType::Radio`,
+//! `GraphType::Area`, and `GraphType::Mirror`.
+//! Every kind of curve is turned into cubic Béziers
+//! so that surfaces only need `bezier_curve_to`.
+//! Arcs are as well, for surfaces which can't draw them.
+
+use serde::{Deserialize, Serialize};
+use std::f64::consts::PI;
+use std::str::FromStr;
+
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, Default, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum CurveType {
+    /// Straight lines between the vertices.
+    #[default]
+    Linear,
+    /// Passes through every vertex.
+    CatmullRom,
+    /// Passes through every vertex without
+    /// overshooting above or below them.
+    Monotone,
+    /// Passes through the midpoints between the vertices,
+    /// using the vertices as the control points.
+    Quadratic,
+}
+
+impl FromStr for CurveType {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "linear" => Ok(CurveType::Linear),
+            "catmull-rom" => Ok(CurveType::CatmullRom),
+            "monotone" => Ok(CurveType::Monotone),
+            "quadratic" => Ok(CurveType::Quadratic),
+            _ => Err(format!("No such curve: {}", name)),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct CurveConfig {
+    #[serde(rename = "type")]
+    pub kind: CurveType,
+    /// Between `0.0` (the loosest) and `1.0` (straight lines).
+    /// Not used for `Quadratic`.
+    pub tension: f64,
+}
+
+impl Default for CurveConfig {
+    fn default() -> CurveConfig {
+        CurveConfig {
+            kind: CurveType::Linear,
+            tension: 0.0,
+        }
+    }
+}
+
+/// One piece of the curve, from where the previous one ended.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Bezier {
+    pub c1: (f64, f64),
+    pub c2: (f64, f64),
+    pub to: (f64, f64),
+}
+
+impl Bezier {
+    /// A straight line.
+    fn line(from: (f64, f64), to: (f64, f64)) -> Bezier {
+        Bezier {
+            c1: lerp2(from, to, 1.0 / 3.0),
+            c2: lerp2(from, to, 2.0 / 3.0),
+            to,
+        }
+    }
+
+    /// The point at `t` (`0.0` to `1.0`) when started from `from`.
+    pub fn at(&self, from: (f64, f64), t: f64) -> (f64, f64) {
+        let r = 1.0 - t;
+        let f = |p0: f64, p1: f64, p2: f64, p3: f64| {
+            r * r * r * p0 + 3.0 * r * r * t * p1 + 3.0 * r * t * t * p2 + t * t * t * p3
+        };
+        (
+            f(from.0, self.c1.0, self.c2.0, self.to.0),
+            f(from.1, self.c1.1, self.c2.1, self.to.1),
+        )
+    }
+}
+
+fn lerp2(a: (f64, f64), b: (f64, f64), t: f64) -> (f64, f64) {
+    (a.0 + (b.0 - a.0) * t, a.1 + (b.1 - a.1) * t)
+}
+
+/// Curves from `points[0]` through the rest.
+/// For `Quadratic`, only the first and the last are passed through.
+pub fn curve(points: &[(f64, f64)], config: &CurveConfig) -> Vec<Bezier> {
+    if points.len() < 2 {
+        return vec![];
+    }
+    let tension = config.tension.clamp(0.0, 1.0);
+    match config.kind {
+        CurveType::Linear => points
+            .windows(2)
+            .map(|w| Bezier::line(w[0], w[1]))
+            .collect(),
+        CurveType::CatmullRom => hermite(points, &catmull_rom_tangents(points, tension)),
+        CurveType::Monotone => monotone(points, tension),
+        CurveType::Quadratic => quadratic(points),
+    }
+}
+
+/// Samples the curve `steps` times per piece,
+/// starting from `points[0]`. For those which
+/// can't draw Béziers, and for testing.
+pub fn evaluate(points: &[(f64, f64)], config: &CurveConfig, steps: usize) -> Vec<(f64, f64)> {
+    let mut out = Vec::with_capacity(points.len() * steps.max(1));
+    let mut from = match points.first() {
+        Some(p) => *p,
+        None => return out,
+    };
+    out.push(from);
+    for bezier in curve(points, config) {
+        for i in 1..=steps.max(1) {
+            out.push(bezier.at(from, i as f64 / steps.max(1) as f64));
+        }
+        from = bezier.to;
+    }
+    out
+}
+
+/// Approximates the arc centered at `(x, y)` from `start` to `end`
+/// (in radian, clockwise on canvas) with pieces of up to 90 degrees.
+/// Returns where it starts, and the pieces from there.
+pub fn arc(x: f64, y: f64, radius: f64, start: f64, end: f64) -> ((f64, f64), Vec<Bezier>) {
+    let sweep = (end - start).clamp(-2.0 * PI, 2.0 * PI);
+    let pieces = (sweep.abs() / (PI / 2.0)).ceil().max(1.0) as usize;
+    let step = sweep / pieces as f64;
+    // Distance of the control points along the tangents.
+    let k = 4.0 / 3.0 * (step / 4.0).tan() * radius;
+    let at = |angle: f64| (x + radius * angle.cos(), y + radius * angle.sin());
+
+    let beziers = (0..pieces)
+        .map(|i| {
+            let (a0, a1) = (start + step * i as f64, start + step * (i + 1) as f64);
+            let (p0, p1) = (at(a0), at(a1));
+            Bezier {
+                c1: (p0.0 - k * a0.sin(), p0.1 + k * a0.cos()),
+                c2: (p1.0 + k * a1.sin(), p1.1 - k * a1.cos()),
+                to: p1,
+            }
+        })
+        .collect();
+    (at(start), beziers)
+}
+
+/// Pieces between the vertices with the given tangents
+/// (in the unit of `dy` per segment, for both `x` and `y`).
+fn hermite(points: &[(f64, f64)], tangents: &[(f64, f64)]) -> Vec<Bezier> {
+    (0..points.len() - 1)
+        .map(|i| {
+            let (p1, p2) = (points[i], points[i + 1]);
+            let (m1, m2) = (tangents[i], tangents[i + 1]);
+            Bezier {
+                c1: (p1.0 + m1.0 / 3.0, p1.1 + m1.1 / 3.0),
+                c2: (p2.0 - m2.0 / 3.0, p2.1 - m2.1 / 3.0),
+                to: p2,
+            }
+        })
+        .collect()
+}
+
+/// Cardinal spline, which is Catmull-Rom when `tension` is `0.0`.
+/// Both ends use the one sided difference.
+fn catmull_rom_tangents(points: &[(f64, f64)], tension: f64) -> Vec<(f64, f64)> {
+    let n = points.len();
+    let scale = 1.0 - tension;
+    (0..n)
+        .map(|i| {
+            let prev = points[i.saturating_sub(1)];
+            let next = points[(i + 1).min(n - 1)];
+            let span = ((i + 1).min(n - 1) - i.saturating_sub(1)) as f64;
+            (
+                scale * (next.0 - prev.0) / span,
+                scale * (next.1 - prev.1) / span,
+            )
+        })
+        .collect()
+}
+
+/// Pieces between the vertices with the slopes of `monotone_slopes`.
+/// Both ends of a piece are scaled by the width of that piece,
+/// so that the curve stays monotone however uneven the spacing.
+fn monotone(points: &[(f64, f64)], tension: f64) -> Vec<Bezier> {
+    let m = monotone_slopes(points);
+    points
+        .windows(2)
+        .enumerate()
+        .map(|(i, w)| {
+            let h = (1.0 - tension) * (w[1].0 - w[0].0) / 3.0;
+            Bezier {
+                c1: (w[0].0 + h, w[0].1 + m[i] * h),
+                c2: (w[1].0 - h, w[1].1 - m[i + 1] * h),
+                to: w[1],
+            }
+        })
+        .collect()
+}
+
+/// Fritsch-Carlson, assuming `x` only grows.
+/// Slopes are kept flat where the vertices turn back,
+/// and are limited so that the curve never overshoots.
+fn monotone_slopes(points: &[(f64, f64)]) -> Vec<f64> {
+    let n = points.len();
+    let dx: Vec<f64> = points.windows(2).map(|w| w[1].0 - w[0].0).collect();
+    let slopes: Vec<f64> = points
+        .windows(2)
+        .zip(dx.iter())
+        .map(|(w, dx)| {
+            if *dx != 0.0 {
+                (w[1].1 - w[0].1) / dx
+            } else {
+                0.0
+            }
+        })
+        .collect();
+
+    let mut m: Vec<f64> = (0..n)
+        .map(|i| {
+            if i == 0 {
+                slopes[0]
+            } else if i == n - 1 {
+                slopes[n - 2]
+            } else if slopes[i - 1] * slopes[i] <= 0.0 {
+                0.0
+            } else {
+                (slopes[i - 1] + slopes[i]) / 2.0
+            }
+        })
+        .collect();
+
+    for i in 0..n - 1 {
+        if slopes[i] == 0.0 {
+            m[i] = 0.0;
+            m[i + 1] = 0.0;
+            continue;
+        }
+        let a = m[i] / slopes[i];
+        let b = m[i + 1] / slopes[i];
+        let h = a * a + b * b;
+        if h > 9.0 {
+            let t = 3.0 / h.sqrt();
+            m[i] = t * a * slopes[i];
+            m[i + 1] = t * b * slopes[i];
+        }
+    }
+
+    m
+}
+
+/// Quadratic Béziers through the midpoints, raised to cubic.
+fn quadratic(points: &[(f64, f64)]) -> Vec<Bezier> {
+    let n = points.len();
+    if n == 2 {
+        return vec![Bezier::line(points[0], points[1])];
+    }
+    let mid = |i: usize| lerp2(points[i], points[i + 1], 0.5);
+    let to_cubic = |from: (f64, f64), control: (f64, f64), to: (f64, f64)| Bezier {
+        c1: lerp2(from, control, 2.0 / 3.0),
+        c2: lerp2(to, control, 2.0 / 3.0),
+        to,
+    };
+
+    let mut out = vec![Bezier::line(points[0], mid(0))];
+    for (i, control) in points.iter().enumerate().take(n - 1).skip(1) {
+        out.push(to_cubic(mid(i - 1), *control, mid(i)));
+    }
+    out.push(Bezier::line(mid(n - 2), points[n - 1]));
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn zigzag() -> Vec<(f64, f64)> {
+        (0..8)
+            .map(|i| (i as f64 * 10.0, if i % 2 == 0 { 0.0 } else { 10.0 }))
+            .collect()
+    }
+
+    fn config(kind: CurveType) -> CurveConfig {
+        CurveConfig { kind, tension: 0.0 }
+    }
+
+    #[test]
+    fn passes_through_every_vertex() {
+        let points = zigzag();
+        for kind in [
+            CurveType::Linear,
+            CurveType::CatmullRom,
+            CurveType::Monotone,
+        ]
+        .iter()
+        {
+            let beziers = curve(&points, &config(*kind));
+            assert_eq!(beziers.len(), points.len() - 1);
+            for (bezier, p) in beziers.iter().zip(points[1..].iter()) {
+                assert_eq!(bezier.to, *p);
+            }
+        }
+    }
+
+    #[test]
+    fn quadratic_goes_through_the_midpoints() {
+        let points = zigzag();
+        let beziers = curve(&points, &config(CurveType::Quadratic));
+        assert_eq!(beziers.len(), points.len());
+        assert_eq!(beziers[1].to, (15.0, 5.0));
+        assert_eq!(beziers.last().unwrap().to, points[points.len() - 1]);
+    }
+
+    #[test]
+    fn monotone_never_overshoots() {
+        let step: Vec<(f64, f64)> = (0..6)
+            .map(|i| (i as f64 * 10.0, if i < 3 { 0.0 } else { 10.0 }))
+            .collect();
+        let samples = evaluate(&step, &config(CurveType::Monotone), 16);
+        assert!(samples
+            .iter()
+            .all(|(_, y)| *y >= -1e-9 && *y <= 10.0 + 1e-9));
+
+        // Whereas Catmull-Rom does, as long as it's loose.
+        let samples = evaluate(&step, &config(CurveType::CatmullRom), 16);
+        assert!(samples.iter().any(|(_, y)| *y < 0.0 || *y > 10.0));
+    }
+
+    #[test]
+    fn monotone_with_uneven_spacing() {
+        let points = [(0.0, 0.0), (1.0, 4.0), (10.0, 5.0), (11.0, 9.0)];
+        let samples = evaluate(&points, &config(CurveType::Monotone), 16);
+        assert!(samples
+            .windows(2)
+            .all(|w| w[1].0 >= w[0].0 - 1e-9 && w[1].1 >= w[0].1 - 1e-9));
+    }
+
+    #[test]
+    fn arc_stays_on_the_circle() {
+        let (from, beziers) = arc(5.0, 5.0, 2.0, 0.0, 2.0 * PI);
+        assert_eq!(beziers.len(), 4);
+        let mut from = from;
+        for bezier in beziers {
+            for i in 0..=8 {
+                let (x, y) = bezier.at(from, i as f64 / 8.0);
+                let r = ((x - 5.0).powi(2) + (y - 5.0).powi(2)).sqrt();
+                assert!((r - 2.0).abs() < 0.01);
+            }
+            from = bezier.to;
+        }
+        assert!((from.0 - 7.0).abs() < 1e-9 && (from.1 - 5.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn full_tension_is_straight() {
+        let points = zigzag();
+        let tight = CurveConfig {
+            kind: CurveType::CatmullRom,
+            tension: 1.0,
+        };
+        let mut from = points[0];
+        for bezier in curve(&points, &tight) {
+            assert_eq!(bezier.c1, from);
+            assert_eq!(bezier.c2, bezier.to);
+            from = bezier.to;
+        }
+    }
+}
@@ -0,0 +1,232 @@
+//! Numbers shown in the readout of the control panel.
+
+use serde::{Deserialize, Serialize};
+
+use crate::perlin::NoiseAlgorithm;
+use crate::types::Point;
+
+/// Over the points of a frame.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Stats {
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+    /// Root mean square.
+    pub rms: f64,
+    /// How many times the wave crosses the middle.
+    pub crossings: usize,
+    /// Index of the point farthest from the middle.
+    pub peak: usize,
+}
+
+impl Stats {
+    /// All zero when there are no points.
+    pub fn of(points: &[Point]) -> Stats {
+        if points.is_empty() {
+            return Stats::default();
+        }
+        let n = points.len() as f64;
+        let mut stats = Stats {
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+            ..Stats::default()
+        };
+        let (mut sum, mut squares) = (0.0, 0.0);
+        for (i, p) in points.iter().enumerate() {
+            stats.min = stats.min.min(p.y);
+            stats.max = stats.max.max(p.y);
+            sum += p.y;
+            squares += p.y * p.y;
+            if p.y.abs() > points[stats.peak].y.abs() {
+                stats.peak = i;
+            }
+        }
+        stats.mean = sum / n;
+        stats.rms = (squares / n).sqrt();
+        // Points right at the middle belong to neither side.
+        let signs: Vec<bool> = points
+            .iter()
+            .filter(|p| p.y != 0.0)
+            .map(|p| p.y > 0.0)
+            .collect();
+        stats.crossings = signs.windows(2).filter(|w| w[0] != w[1]).count();
+        stats
+    }
+}
+
+/// What the readout has always shown: the first point times 10.
+pub fn control_value(points: &[Point]) -> f64 {
+    points.first().map_or(0.0, |p| p.y.abs()) * 10.0
+}
+
+/// Averages the time between frames of the animation loop.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct FrameRate {
+    /// Timestamp last recorded.
+    last: Option<f64>,
+    /// In milliseconds. `None` until two frames are recorded.
+    frame_time: Option<f64>,
+}
+
+impl FrameRate {
+    /// How much a new frame weighs in the average.
+    const WEIGHT: f64 = 0.1;
+
+    /// `now` is in milliseconds.
+    pub fn record(&mut self, now: f64) {
+        if let Some(last) = self.last {
+            let delta = (now - last).max(0.0);
+            self.frame_time = Some(match self.frame_time {
+                Some(average) => average + (delta - average) * FrameRate::WEIGHT,
+                None => delta,
+            });
+        }
+        self.last = Some(now);
+    }
+
+    /// So that the time spent paused is not counted.
+    pub fn pause(&mut self) {
+        self.last = None;
+    }
+
+    pub fn frame_time(&self) -> Option<f64> {
+        self.frame_time
+    }
+
+    pub fn fps(&self) -> Option<f64> {
+        self.frame_time.filter(|t| *t > 0.0).map(|t| 1000.0 / t)
+    }
+}
+
+/// What the readout shows besides the points.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Readout {
+    pub seed: u64,
+    pub noise: NoiseAlgorithm,
+    pub rate: FrameRate,
+}
+
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Metric {
+    /// `control_value`, shown without a label.
+    Value,
+    Min,
+    Max,
+    Mean,
+    Rms,
+    Crossings,
+    Peak,
+    Seed,
+    Noise,
+    Fps,
+    FrameTime,
+}
+
+impl Metric {
+    pub fn label(self) -> &'static str {
+        match self {
+            Metric::Value => "",
+            Metric::Min => "min",
+            Metric::Max => "max",
+            Metric::Mean => "mean",
+            Metric::Rms => "rms",
+            Metric::Crossings => "zero",
+            Metric::Peak => "peak",
+            Metric::Seed => "seed",
+            Metric::Noise => "noise",
+            Metric::Fps => "fps",
+            Metric::FrameTime => "ms",
+        }
+    }
+
+    /// `-` for the frame rate until it is known.
+    pub fn value(self, points: &[Point], stats: &Stats, readout: &Readout) -> String {
+        let or_dash = |v: Option<f64>, f: fn(f64) -> String| v.map(f).unwrap_or_else(|| "-".into());
+        match self {
+            Metric::Value => format!("{:.5}", control_value(points)),
+            Metric::Min => format!("{:.3}", stats.min),
+            Metric::Max => format!("{:.3}", stats.max),
+            Metric::Mean => format!("{:.3}", stats.mean),
+            Metric::Rms => format!("{:.3}", stats.rms),
+            Metric::Crossings => stats.crossings.to_string(),
+            Metric::Peak => stats.peak.to_string(),
+            Metric::Seed => readout.seed.to_string(),
+            Metric::Noise => format!("{:?}", readout.noise).to_lowercase(),
+            Metric::Fps => or_dash(readout.rate.fps(), |v| format!("{:.0}", v)),
+            Metric::FrameTime => or_dash(readout.rate.frame_time(), |v| format!("{:.1}", v)),
+        }
+    }
+
+    /// The label and the value.
+    pub fn text(self, points: &[Point], stats: &Stats, readout: &Readout) -> String {
+        let value = self.value(points, stats, readout);
+        match self.label() {
+            "" => value,
+            label => format!("{} {}", label, value),
+        }
+    }
+}
+
+/// How the metrics are laid out in the readout.
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ReadoutMode {
+    /// In a single line.
+    #[default]
+    Compact,
+    /// One per row, in as many columns as needed.
+    Expanded,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn points(ys: &[f64]) -> Vec<Point> {
+        ys.iter()
+            .enumerate()
+            .map(|(i, y)| Point { x: i as f64, y: *y })
+            .collect()
+    }
+
+    #[test]
+    fn over_the_points() {
+        let stats = Stats::of(&points(&[0.5, -0.5, 0.0, -0.5, 1.0]));
+        assert_eq!((stats.min, stats.max), (-0.5, 1.0));
+        assert_eq!(stats.mean, 0.1);
+        assert_eq!(stats.rms, (1.75_f64 / 5.0).sqrt());
+        assert_eq!(stats.crossings, 2);
+        assert_eq!(stats.peak, 4);
+        assert_eq!(Stats::of(&[]), Stats::default());
+    }
+
+    #[test]
+    fn frame_rate() {
+        let mut rate = FrameRate::default();
+        rate.record(0.0);
+        assert_eq!(rate.fps(), None);
+        rate.record(20.0);
+        assert_eq!(rate.fps(), Some(50.0));
+        rate.pause();
+        rate.record(5000.0);
+        rate.record(5030.0);
+        assert_eq!(rate.frame_time(), Some(21.0));
+
+        let readout = Readout {
+            seed: 7,
+            noise: NoiseAlgorithm::SuperSimplex,
+            rate,
+        };
+        let stats = Stats::default();
+        assert_eq!(Metric::FrameTime.text(&[], &stats, &readout), "ms 21.0");
+        assert_eq!(
+            Metric::Noise.text(&[], &stats, &readout),
+            "noise supersimplex"
+        );
+        assert_eq!(
+            Metric::Value.text(&points(&[-0.5]), &stats, &readout),
+            "5.00000"
+        );
+    }
+}
@@ -0,0 +1,302 @@
+use std::any::Any;
+
+/// Where the renderers draw.
+/// Modeled after `CanvasRenderingContext2d`
+/// so that the 2D canvas is a thin implementation,
+/// but anything else can implement it as well.
+pub trait Surface: Any {
+    fn as_any(&self) -> &dyn Any;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+
+    /// Erases everything drawn so far.
+    fn clear(&mut self);
+
+    fn save(&mut self);
+    fn restore(&mut self);
+
+    fn translate(&mut self, x: f64, y: f64);
+    /// `angle` in radian.
+    fn rotate(&mut self, angle: f64);
+
+    /// Between `0.0` and `1.0`, applied to whatever drawn
+    /// afterwards. Saved and restored with the rest.
+    fn set_global_alpha(&mut self, alpha: f64);
+    fn set_fill_style(&mut self, color: &str);
+    /// Fills with the gradient instead of a color
+    /// until `set_fill_style` is called again.
+    fn set_fill_gradient(&mut self, gradient: &Gradient);
+    fn set_stroke_style(&mut self, color: &str);
+    /// Strokes with the gradient instead of a color
+    /// until `set_stroke_style` is called again.
+    fn set_stroke_gradient(&mut self, gradient: &Gradient);
+    fn set_font(&mut self, font: &str);
+    /// For `stroke`. `1.0` until set.
+    fn set_line_width(&mut self, width: f64);
+    /// Blurs a copy of whatever drawn afterwards in `color`
+    /// behind it, which makes it glow. None when `blur` is `0.0`.
+    fn set_shadow(&mut self, blur: f64, color: &str);
+
+    fn begin_path(&mut self);
+    fn move_to(&mut self, x: f64, y: f64);
+    fn line_to(&mut self, x: f64, y: f64);
+    /// Cubic Bézier from the current point to `(x, y)`
+    /// with the control points `(c1x, c1y)` and `(c2x, c2y)`.
+    fn bezier_curve_to(&mut self, c1x: f64, c1y: f64, c2x: f64, c2y: f64, x: f64, y: f64);
+    /// Arc centered at `(x, y)`, clockwise from `start` to `end`
+    /// (in radian). Joined to the current point with a line, if any.
+    fn arc(&mut self, x: f64, y: f64, radius: f64, start: f64, end: f64);
+    fn close_path(&mut self);
+    fn stroke(&mut self);
+    fn fill(&mut self);
+
+    fn fill_rect(&mut self, x: f64, y: f64, width: f64, height: f64);
+    fn fill_text(&mut self, text: &str, x: f64, y: f64);
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum GradientShape {
+    /// Along the line from `(x0, y0)` to `(x1, y1)`.
+    Linear { x0: f64, y0: f64, x1: f64, y1: f64 },
+    /// Circles around `(x, y)` from the radius `r0` to `r1`.
+    Radial { x: f64, y: f64, r0: f64, r1: f64 },
+}
+
+/// `stops` are pairs of an offset (`0.0` to `1.0`) and a color.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Gradient {
+    pub shape: GradientShape,
+    pub stops: Vec<(f64, String)>,
+}
+
+impl Gradient {
+    /// With the points transformed by `m`. Radii stay
+    /// the same since `Matrix` never scales.
+    pub fn transform(&self, m: &Matrix) -> Gradient {
+        let shape = match self.shape {
+            GradientShape::Linear { x0, y0, x1, y1 } => {
+                let (x0, y0) = m.apply(x0, y0);
+                let (x1, y1) = m.apply(x1, y1);
+                GradientShape::Linear { x0, y0, x1, y1 }
+            }
+            GradientShape::Radial { x, y, r0, r1 } => {
+                let (x, y) = m.apply(x, y);
+                GradientShape::Radial { x, y, r0, r1 }
+            }
+        };
+        Gradient {
+            shape,
+            stops: self.stops.clone(),
+        }
+    }
+
+    /// Stops of a radial gradient starting from the center
+    /// instead of `r0`, for backends which only have `r1`.
+    pub fn stops_from_center(&self) -> Vec<(f64, String)> {
+        match self.shape {
+            GradientShape::Radial { r0, r1, .. } if r1 > 0.0 => self
+                .stops
+                .iter()
+                .map(|(offset, color)| ((r0 + offset * (r1 - r0)) / r1, color.clone()))
+                .collect(),
+            _ => self.stops.clone(),
+        }
+    }
+}
+
+/// 2D affine transform, the same as `CanvasRenderingContext2d` keeps.
+/// For backends which need to track the transform by themselves.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Matrix {
+    pub a: f64,
+    pub b: f64,
+    pub c: f64,
+    pub d: f64,
+    pub e: f64,
+    pub f: f64,
+}
+
+impl Matrix {
+    pub const IDENTITY: Matrix = Matrix {
+        a: 1.0,
+        b: 0.0,
+        c: 0.0,
+        d: 1.0,
+        e: 0.0,
+        f: 0.0,
+    };
+
+    pub fn translate(&self, x: f64, y: f64) -> Matrix {
+        Matrix {
+            e: self.a * x + self.c * y + self.e,
+            f: self.b * x + self.d * y + self.f,
+            ..*self
+        }
+    }
+
+    /// `angle` in radian.
+    pub fn rotate(&self, angle: f64) -> Matrix {
+        let (sin, cos) = angle.sin_cos();
+        Matrix {
+            a: self.a * cos + self.c * sin,
+            b: self.b * cos + self.d * sin,
+            c: self.c * cos - self.a * sin,
+            d: self.d * cos - self.b * sin,
+            ..*self
+        }
+    }
+
+    pub fn apply(&self, x: f64, y: f64) -> (f64, f64) {
+        (
+            self.a * x + self.c * y + self.e,
+            self.b * x + self.d * y + self.f,
+        )
+    }
+}
+
+#[cfg(test)]
+pub mod mock {
+    use super::*;
+
+    /// Whatever was called on `Recorder`.
+    #[derive(Clone, Debug, PartialEq)]
+    pub enum Call {
+        Clear,
+        Save,
+        Restore,
+        Translate(f64, f64),
+        Rotate(f64),
+        GlobalAlpha(f64),
+        FillStyle(String),
+        FillGradient(Gradient),
+        StrokeStyle(String),
+        StrokeGradient(Gradient),
+        Font(String),
+        LineWidth(f64),
+        Shadow(f64, String),
+        BeginPath,
+        MoveTo(f64, f64),
+        LineTo(f64, f64),
+        BezierCurveTo(f64, f64, f64, f64, f64, f64),
+        Arc(f64, f64, f64, f64, f64),
+        ClosePath,
+        Stroke,
+        Fill,
+        FillRect(f64, f64, f64, f64),
+        FillText(String, f64, f64),
+    }
+
+    /// Instead of drawing, records the calls.
+    #[derive(Default)]
+    pub struct Recorder {
+        pub calls: Vec<Call>,
+    }
+
+    impl Surface for Recorder {
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+
+        fn as_any_mut(&mut self) -> &mut dyn Any {
+            self
+        }
+
+        fn clear(&mut self) {
+            self.calls.push(Call::Clear);
+        }
+
+        fn save(&mut self) {
+            self.calls.push(Call::Save);
+        }
+
+        fn restore(&mut self) {
+            self.calls.push(Call::Restore);
+        }
+
+        fn translate(&mut self, x: f64, y: f64) {
+            self.calls.push(Call::Translate(x, y));
+        }
+
+        fn rotate(&mut self, angle: f64) {
+            self.calls.push(Call::Rotate(angle));
+        }
+
+        fn set_global_alpha(&mut self, alpha: f64) {
+            self.calls.push(Call::GlobalAlpha(alpha));
+        }
+
+        fn set_fill_style(&mut self, color: &str) {
+            self.calls.push(Call::FillStyle(color.into()));
+        }
+
+        fn set_fill_gradient(&mut self, gradient: &Gradient) {
+            self.calls.push(Call::FillGradient(gradient.clone()));
+        }
+
+        fn set_stroke_style(&mut self, color: &str) {
+            self.calls.push(Call::StrokeStyle(color.into()));
+        }
+
+        fn set_stroke_gradient(&mut self, gradient: &Gradient) {
+            self.calls.push(Call::StrokeGradient(gradient.clone()));
+        }
+
+        fn set_font(&mut self, font: &str) {
+            self.calls.push(Call::Font(font.into()));
+        }
+
+        fn set_line_width(&mut self, width: f64) {
+            self.calls.push(Call::LineWidth(width));
+        }
+
+        fn set_shadow(&mut self, blur: f64, color: &str) {
+            self.calls.push(Call::Shadow(blur, color.into()));
+        }
+
+        fn begin_path(&mut self) {
+            self.calls.push(Call::BeginPath);
+        }
+
+        fn move_to(&mut self, x: f64, y: f64) {
+            self.calls.push(Call::MoveTo(x, y));
+        }
+
+        fn line_to(&mut self, x: f64, y: f64) {
+            self.calls.push(Call::LineTo(x, y));
+        }
+
+        fn bezier_curve_to(&mut self, c1x: f64, c1y: f64, c2x: f64, c2y: f64, x: f64, y: f64) {
+            self.calls
+                .push(Call::BezierCurveTo(c1x, c1y, c2x, c2y, x, y));
+        }
+
+        fn arc(&mut self, x: f64, y: f64, radius: f64, start: f64, end: f64) {
+            self.calls.push(Call::Arc(x, y, radius, start, end));
+        }
+
+        fn close_path(&mut self) {
+            self.calls.push(Call::ClosePath);
+        }
+
+        fn stroke(&mut self) {
+            self.calls.push(Call::Stroke);
+        }
+
+        fn fill(&mut self) {
+            self.calls.push(Call::Fill);
+        }
+
+        fn fill_rect(&mut self, x: f64, y: f64, width: f64, height: f64) {
+            self.calls.push(Call::FillRect(x, y, width, height));
+        }
+
+        fn fill_text(&mut self, text: &str, x: f64, y: f64) {
+            self.calls.push(Call::FillText(text.into(), x, y));
+        }
+    }
+
+    /// Takes out the calls recorded in `surface`.
+    pub fn calls(surface: &mut dyn Surface) -> Vec<Call> {
+        let recorder = surface.as_any_mut().downcast_mut::<Recorder>().unwrap();
+        std::mem::take(&mut recorder.calls)
+    }
+}
@@ -0,0 +1,558 @@
+//! Renders a frame into an SVG document instead of canvas.
+
+use std::any::Any;
+
+use crate::graphics::control::ControlGraphics;
+use crate::graphics::wave::WaveGraphics;
+use crate::graphics::Graphics;
+use crate::spline;
+use crate::stats::Readout;
+use crate::surface::{Gradient, GradientShape, Matrix, Surface};
+use crate::timeline::Frame;
+use crate::types::{GraphType, Point};
+
+#[derive(Clone)]
+struct State {
+    matrix: Matrix,
+    /// Either a color, or `url(#...)` of a gradient.
+    fill: String,
+    stroke: String,
+    font: String,
+    alpha: f64,
+    line_width: f64,
+    /// `url(#...)` of the glow, if any.
+    filter: Option<String>,
+}
+
+impl Default for State {
+    fn default() -> Self {
+        State {
+            matrix: Matrix::IDENTITY,
+            alpha: 1.0,
+            fill: "#000".into(),
+            stroke: "#000".into(),
+            font: "10px sans-serif".into(),
+            line_width: 1.0,
+            filter: None,
+        }
+    }
+}
+
+/// `Surface` which collects SVG elements.
+pub struct SvgSurface {
+    width: f64,
+    height: f64,
+    state: State,
+    stack: Vec<State>,
+    path: String,
+    elements: Vec<String>,
+    /// For the ids of the gradients.
+    gradients: usize,
+    /// For the ids of the glows.
+    filters: usize,
+}
+
+impl SvgSurface {
+    pub fn new(width: f64, height: f64) -> SvgSurface {
+        SvgSurface {
+            width,
+            height,
+            state: State::default(),
+            stack: vec![],
+            path: String::new(),
+            elements: vec![],
+            gradients: 0,
+            filters: 0,
+        }
+    }
+
+    /// Whatever drawn so far as an SVG document.
+    pub fn to_svg(&self) -> String {
+        let mut svg = format!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}">"#,
+            w = num(self.width),
+            h = num(self.height)
+        );
+        svg.push('\n');
+        for el in &self.elements {
+            svg.push_str(el);
+            svg.push('\n');
+        }
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
+
+impl SvgSurface {
+    /// Adds the gradient to the document, and returns `url(#...)`
+    /// referring to it. Points are transformed here, since
+    /// the gradient is not a part of the element using it.
+    fn define(&mut self, gradient: &Gradient) -> String {
+        self.gradients += 1;
+        let id = format!("gradient{}", self.gradients);
+        let gradient = gradient.transform(&self.state.matrix);
+        let stops: String = gradient
+            .stops_from_center()
+            .iter()
+            .map(|(offset, color)| {
+                format!(
+                    r#"<stop offset="{}" stop-color="{}"/>"#,
+                    num(*offset),
+                    escape(color)
+                )
+            })
+            .collect();
+        let element = match gradient.shape {
+            GradientShape::Linear { x0, y0, x1, y1 } => format!(
+                r#"<linearGradient id="{}" gradientUnits="userSpaceOnUse" x1="{}" y1="{}" x2="{}" y2="{}">{}</linearGradient>"#,
+                id,
+                num(x0),
+                num(y0),
+                num(x1),
+                num(y1),
+                stops
+            ),
+            GradientShape::Radial { x, y, r1, .. } => format!(
+                r#"<radialGradient id="{}" gradientUnits="userSpaceOnUse" cx="{}" cy="{}" r="{}">{}</radialGradient>"#,
+                id,
+                num(x),
+                num(y),
+                num(r1),
+                stops
+            ),
+        };
+        self.elements.push(format!("<defs>{}</defs>", element));
+        format!("url(#{})", id)
+    }
+
+    /// Adds a filter blurring in `color` as `set_shadow` does,
+    /// and returns `url(#...)` referring to it.
+    fn define_glow(&mut self, blur: f64, color: &str) -> String {
+        self.filters += 1;
+        let id = format!("glow{}", self.filters);
+        // Canvas blurs with the standard deviation of half the blur.
+        self.elements.push(format!(
+            r#"<defs><filter id="{}" x="-50%" y="-50%" width="200%" height="200%"><feDropShadow dx="0" dy="0" stdDeviation="{}" flood-color="{}"/></filter></defs>"#,
+            id,
+            num(blur / 2.0),
+            escape(color)
+        ));
+        format!("url(#{})", id)
+    }
+}
+
+impl Surface for SvgSurface {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn clear(&mut self) {
+        self.elements.clear();
+        self.path.clear();
+        self.gradients = 0;
+        self.filters = 0;
+    }
+
+    fn save(&mut self) {
+        self.stack.push(self.state.clone());
+    }
+
+    fn restore(&mut self) {
+        if let Some(state) = self.stack.pop() {
+            self.state = state;
+        }
+    }
+
+    fn translate(&mut self, x: f64, y: f64) {
+        self.state.matrix = self.state.matrix.translate(x, y);
+    }
+
+    fn rotate(&mut self, angle: f64) {
+        self.state.matrix = self.state.matrix.rotate(angle);
+    }
+
+    fn set_global_alpha(&mut self, alpha: f64) {
+        self.state.alpha = alpha.clamp(0.0, 1.0);
+    }
+
+    fn set_fill_style(&mut self, color: &str) {
+        self.state.fill = color.into();
+    }
+
+    fn set_fill_gradient(&mut self, gradient: &Gradient) {
+        self.state.fill = self.define(gradient);
+    }
+
+    fn set_stroke_style(&mut self, color: &str) {
+        self.state.stroke = color.into();
+    }
+
+    fn set_stroke_gradient(&mut self, gradient: &Gradient) {
+        self.state.stroke = self.define(gradient);
+    }
+
+    fn set_font(&mut self, font: &str) {
+        self.state.font = font.into();
+    }
+
+    fn set_line_width(&mut self, width: f64) {
+        self.state.line_width = width;
+    }
+
+    fn set_shadow(&mut self, blur: f64, color: &str) {
+        self.state.filter = if blur > 0.0 {
+            Some(self.define_glow(blur, color))
+        } else {
+            None
+        };
+    }
+
+    fn begin_path(&mut self) {
+        self.path.clear();
+    }
+
+    // Unlike `fill_rect`, path coordinates are transformed
+    // as they are added, just like canvas does.
+    fn move_to(&mut self, x: f64, y: f64) {
+        let (x, y) = self.state.matrix.apply(x, y);
+        self.path.push_str(&format!("M{} {} ", num(x), num(y)));
+    }
+
+    fn line_to(&mut self, x: f64, y: f64) {
+        let (x, y) = self.state.matrix.apply(x, y);
+        self.path.push_str(&format!("L{} {} ", num(x), num(y)));
+    }
+
+    fn bezier_curve_to(&mut self, c1x: f64, c1y: f64, c2x: f64, c2y: f64, x: f64, y: f64) {
+        let m = self.state.matrix;
+        let (c1x, c1y) = m.apply(c1x, c1y);
+        let (c2x, c2y) = m.apply(c2x, c2y);
+        let (x, y) = m.apply(x, y);
+        self.path.push_str(&format!(
+            "C{} {} {} {} {} {} ",
+            num(c1x),
+            num(c1y),
+            num(c2x),
+            num(c2y),
+            num(x),
+            num(y)
+        ));
+    }
+
+    fn arc(&mut self, x: f64, y: f64, radius: f64, start: f64, end: f64) {
+        let ((x0, y0), beziers) = spline::arc(x, y, radius, start, end);
+        if self.path.is_empty() {
+            self.move_to(x0, y0);
+        } else {
+            self.line_to(x0, y0);
+        }
+        for b in beziers {
+            self.bezier_curve_to(b.c1.0, b.c1.1, b.c2.0, b.c2.1, b.to.0, b.to.1);
+        }
+    }
+
+    fn close_path(&mut self) {
+        self.path.push_str("Z ");
+    }
+
+    fn stroke(&mut self) {
+        self.elements.push(format!(
+            r#"<path d="{}" fill="none" stroke="{}"{}{}{}/>"#,
+            self.path.trim_end(),
+            escape(&self.state.stroke),
+            line_width_attr(self.state.line_width),
+            opacity_attr(self.state.alpha),
+            filter_attr(&self.state.filter)
+        ));
+    }
+
+    fn fill(&mut self) {
+        self.elements.push(format!(
+            r#"<path d="{}" fill="{}"{}{}/>"#,
+            self.path.trim_end(),
+            escape(&self.state.fill),
+            opacity_attr(self.state.alpha),
+            filter_attr(&self.state.filter)
+        ));
+    }
+
+    fn fill_rect(&mut self, x: f64, y: f64, width: f64, height: f64) {
+        // SVG does not allow negative sizes while canvas does.
+        let (x, width) = if width < 0.0 {
+            (x + width, -width)
+        } else {
+            (x, width)
+        };
+        let (y, height) = if height < 0.0 {
+            (y + height, -height)
+        } else {
+            (y, height)
+        };
+        self.elements.push(format!(
+            r#"<rect x="{}" y="{}" width="{}" height="{}" fill="{}"{}{}{}/>"#,
+            num(x),
+            num(y),
+            num(width),
+            num(height),
+            escape(&self.state.fill),
+            opacity_attr(self.state.alpha),
+            filter_attr(&self.state.filter),
+            transform_attr(&self.state.matrix)
+        ));
+    }
+
+    fn fill_text(&mut self, text: &str, x: f64, y: f64) {
+        self.elements.push(format!(
+            r#"<text x="{}" y="{}" fill="{}" style="font: {}"{}{}{}>{}</text>"#,
+            num(x),
+            num(y),
+            escape(&self.state.fill),
+            escape(&self.state.font),
+            opacity_attr(self.state.alpha),
+            filter_attr(&self.state.filter),
+            transform_attr(&self.state.matrix),
+            escape(text)
+        ));
+    }
+}
+
+/// `transform` attribute, or nothing when not transformed.
+fn transform_attr(m: &Matrix) -> String {
+    if *m == Matrix::IDENTITY {
+        String::new()
+    } else {
+        format!(
+            r#" transform="matrix({} {} {} {} {} {})""#,
+            num(m.a),
+            num(m.b),
+            num(m.c),
+            num(m.d),
+            num(m.e),
+            num(m.f)
+        )
+    }
+}
+
+/// `opacity` attribute, or nothing when opaque.
+fn opacity_attr(alpha: f64) -> String {
+    if alpha < 1.0 {
+        format!(r#" opacity="{}""#, num(alpha))
+    } else {
+        String::new()
+    }
+}
+
+/// `stroke-width` attribute, or nothing for the default.
+fn line_width_attr(width: f64) -> String {
+    if width != 1.0 {
+        format!(r#" stroke-width="{}""#, num(width))
+    } else {
+        String::new()
+    }
+}
+
+/// `filter` attribute, or nothing without a glow.
+fn filter_attr(filter: &Option<String>) -> String {
+    match filter {
+        Some(url) => format!(r#" filter="{}""#, url),
+        None => String::new(),
+    }
+}
+
+/// Numbers rounded to 3 decimals so that the document stays small.
+fn num(v: f64) -> String {
+    let v = (v * 1000.0).round() / 1000.0;
+    // Avoiding "-0".
+    format!("{}", if v == 0.0 { 0.0 } else { v })
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Takes the SVG out of graphics drawn on `SvgSurface`.
+fn svg_of(g: &mut dyn Graphics) -> String {
+    match g.surface().as_any().downcast_ref::<SvgSurface>() {
+        Some(svg) => svg.to_svg(),
+        None => String::new(),
+    }
+}
+
+/// A frame of the wave panel as an SVG document,
+/// drawn with the same settings as `g`.
+pub fn wave_to_svg(
+    g: &WaveGraphics,
+    graph_type: GraphType,
+    points: &[Point],
+    points_prev: &[Point],
+    frame: Frame,
+) -> String {
+    let mut g = g.with_surface(Box::new(SvgSurface::new(g.width, g.height)));
+    g.clear();
+    g.render(graph_type, points, points_prev, frame);
+    svg_of(&mut g)
+}
+
+/// A frame of the control panel as an SVG document,
+/// drawn with the same settings as `g`.
+pub fn control_to_svg(g: &ControlGraphics, points: &[Point], readout: &Readout) -> String {
+    let mut g = g.with_surface(Box::new(SvgSurface::new(g.width, g.height)));
+    g.clear();
+    g.render_control(points, readout);
+    svg_of(&mut g)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::SEGMENTS;
+    use crate::perlin::NoiseAlgorithm;
+    use crate::stats::FrameRate;
+
+    fn points(y: f64) -> Vec<Point> {
+        vec![Point { x: 0.0, y }; SEGMENTS]
+    }
+
+    #[test]
+    fn rect_with_negative_height() {
+        let mut s = SvgSurface::new(10.0, 10.0);
+        s.set_fill_style("red");
+        s.fill_rect(1.0, 5.0, 2.0, -3.0);
+        assert!(s
+            .to_svg()
+            .contains(r#"<rect x="1" y="2" width="2" height="3" fill="red"/>"#));
+    }
+
+    #[test]
+    fn alpha_is_saved_and_restored() {
+        let mut s = SvgSurface::new(10.0, 10.0);
+        s.save();
+        s.set_global_alpha(0.25);
+        s.fill_rect(0.0, 0.0, 1.0, 1.0);
+        s.restore();
+        s.fill_rect(0.0, 0.0, 1.0, 1.0);
+        let svg = s.to_svg();
+        assert!(svg.contains(r##"fill="#000" opacity="0.25"/>"##));
+        assert!(svg.contains(r##"fill="#000"/>"##));
+    }
+
+    #[test]
+    fn wide_and_glowing() {
+        let mut s = SvgSurface::new(10.0, 10.0);
+        s.set_line_width(2.0);
+        s.set_shadow(4.0, "cyan");
+        s.begin_path();
+        s.move_to(0.0, 0.0);
+        s.line_to(1.0, 2.0);
+        s.stroke();
+        let svg = s.to_svg();
+        assert!(svg.contains(r#"stdDeviation="2" flood-color="cyan""#));
+        assert!(svg.contains(r#"stroke-width="2" filter="url(#glow1)"/>"#));
+    }
+
+    #[test]
+    fn path_is_transformed() {
+        let mut s = SvgSurface::new(10.0, 10.0);
+        s.save();
+        s.translate(5.0, 5.0);
+        s.begin_path();
+        s.move_to(0.0, 0.0);
+        s.line_to(1.0, 2.0);
+        s.stroke();
+        s.restore();
+        assert!(s.to_svg().contains(r#"d="M5 5 L6 7""#));
+    }
+
+    #[test]
+    fn filled_with_a_gradient() {
+        let mut s = SvgSurface::new(10.0, 10.0);
+        s.set_fill_gradient(&Gradient {
+            shape: GradientShape::Linear {
+                x0: 0.0,
+                y0: 0.0,
+                x1: 0.0,
+                y1: 10.0,
+            },
+            stops: vec![(0.0, "red".into()), (1.0, "blue".into())],
+        });
+        s.begin_path();
+        s.move_to(0.0, 0.0);
+        s.line_to(10.0, 10.0);
+        s.line_to(0.0, 10.0);
+        s.close_path();
+        s.fill();
+        let svg = s.to_svg();
+        assert!(svg.contains(r#"<stop offset="1" stop-color="blue"/>"#));
+        assert!(svg.contains(r#"<path d="M0 0 L10 10 L0 10 Z" fill="url(#gradient1)"/>"#));
+    }
+
+    #[test]
+    fn radial_stroke_from_the_inner_radius() {
+        let mut s = SvgSurface::new(10.0, 10.0);
+        s.translate(5.0, 5.0);
+        s.set_stroke_gradient(&Gradient {
+            shape: GradientShape::Radial {
+                x: 0.0,
+                y: 0.0,
+                r0: 2.0,
+                r1: 4.0,
+            },
+            stops: vec![(0.0, "red".into()), (1.0, "blue".into())],
+        });
+        s.begin_path();
+        s.move_to(0.0, 0.0);
+        s.line_to(1.0, 1.0);
+        s.stroke();
+        let svg = s.to_svg();
+        assert!(svg.contains(r#"cx="5" cy="5" r="4"><stop offset="0.5" stop-color="red"/>"#));
+        assert!(svg.contains(r#"stroke="url(#gradient1)""#));
+    }
+
+    #[test]
+    fn every_graph_type() {
+        let g = WaveGraphics::new(
+            Box::new(SvgSurface::new(550.0, 200.0)),
+            550.0,
+            200.0,
+            "#222",
+            "#c0e822",
+        );
+        for graph_type in GraphType::ALL.iter() {
+            let svg = wave_to_svg(
+                &g,
+                *graph_type,
+                &points(0.5),
+                &points(0.0),
+                Frame::middle(350.0),
+            );
+            assert!(svg.starts_with("<svg "));
+            assert!(svg.trim_end().ends_with("</svg>"));
+            assert!(svg.contains("#c0e822"));
+        }
+    }
+
+    #[test]
+    fn control_readout() {
+        let g = ControlGraphics::new(
+            Box::new(SvgSurface::new(300.0, 20.0)),
+            300.0,
+            20.0,
+            "#222",
+            "#c0e822",
+        );
+        let readout = Readout {
+            seed: 1,
+            noise: NoiseAlgorithm::default(),
+            rate: FrameRate::default(),
+        };
+        let svg = control_to_svg(&g, &points(0.5), &readout);
+        assert!(svg.contains(">5.00000</text>"));
+    }
+}
@@ -0,0 +1,135 @@
+//! Colors, font, and lines shared by every panel,
+//! either one of the presets or given as JSON.
+
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+use crate::constants::FONT_FAMILY;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct Theme {
+    pub background: String,
+    /// For the wave.
+    pub primary: String,
+    /// For the text of the control panel.
+    pub secondary: String,
+    /// For the glow.
+    pub accent: String,
+    /// Font family.
+    pub font: String,
+    pub line_width: f64,
+    /// Blur of the glow in pixels. No glow when `0`.
+    pub glow: f64,
+}
+
+impl Default for Theme {
+    fn default() -> Theme {
+        Theme::dark()
+    }
+}
+
+impl Theme {
+    pub fn dark() -> Theme {
+        Theme {
+            background: "#222".into(),
+            primary: "#759203".into(),
+            secondary: "#c0e822".into(),
+            accent: "#c0e822".into(),
+            font: FONT_FAMILY.into(),
+            line_width: 1.0,
+            glow: 0.0,
+        }
+    }
+
+    pub fn light() -> Theme {
+        Theme {
+            background: "#f5f5f0".into(),
+            primary: "#4a7a00".into(),
+            secondary: "#2b3a12".into(),
+            accent: "#8cc010".into(),
+            font: FONT_FAMILY.into(),
+            line_width: 1.5,
+            glow: 0.0,
+        }
+    }
+
+    pub fn high_contrast() -> Theme {
+        Theme {
+            background: "#000".into(),
+            primary: "#ffff00".into(),
+            secondary: "#fff".into(),
+            accent: "#00ffff".into(),
+            font: FONT_FAMILY.into(),
+            line_width: 2.0,
+            glow: 4.0,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum ThemeName {
+    Dark,
+    Light,
+    HighContrast,
+}
+
+impl FromStr for ThemeName {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "dark" => Ok(ThemeName::Dark),
+            "light" => Ok(ThemeName::Light),
+            "high-contrast" => Ok(ThemeName::HighContrast),
+            _ => Err(format!("No such theme: {}", name)),
+        }
+    }
+}
+
+/// Either the name of a preset, or the whole theme
+/// (where the fields left out are of `dark`).
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(untagged)]
+pub enum ThemeSpec {
+    Named(ThemeName),
+    Custom(Theme),
+}
+
+impl Default for ThemeSpec {
+    fn default() -> ThemeSpec {
+        ThemeSpec::Named(ThemeName::Dark)
+    }
+}
+
+impl ThemeSpec {
+    pub fn theme(&self) -> Theme {
+        match self {
+            ThemeSpec::Named(ThemeName::Dark) => Theme::dark(),
+            ThemeSpec::Named(ThemeName::Light) => Theme::light(),
+            ThemeSpec::Named(ThemeName::HighContrast) => Theme::high_contrast(),
+            ThemeSpec::Custom(theme) => theme.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn named_or_custom() {
+        let named: ThemeSpec = serde_json::from_str(r#""high-contrast""#).unwrap();
+        assert_eq!(named.theme(), Theme::high_contrast());
+
+        let custom: ThemeSpec =
+            serde_json::from_str(r##"{ "background": "#fff", "glow": 2 }"##).unwrap();
+        let theme = custom.theme();
+        assert_eq!(theme.background, "#fff");
+        assert_eq!(theme.glow, 2.0);
+        assert_eq!(theme.primary, Theme::dark().primary);
+
+        assert!(serde_json::from_str::<ThemeSpec>(r#"{ "colour": "red" }"#).is_err());
+    }
+}
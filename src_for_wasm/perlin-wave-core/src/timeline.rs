@@ -0,0 +1,190 @@
+use crate::constants::CYCLE_MS;
+
+/// Where a frame is within the cycle, in milliseconds.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Frame {
+    /// Between `0.0` and `cycle`.
+    pub elapsed: f64,
+    /// Length of the cycle.
+    pub cycle: f64,
+    /// Since the very first frame.
+    pub time: f64,
+}
+
+impl Default for Frame {
+    fn default() -> Frame {
+        Frame {
+            elapsed: 0.0,
+            cycle: CYCLE_MS,
+            time: 0.0,
+        }
+    }
+}
+
+impl Frame {
+    /// In the middle of the cycle, where `GraphType::Radio`
+    /// is at its full height. So are the other types
+    /// as long as `points_prev` are the same as `points`.
+    pub fn middle(cycle: f64) -> Frame {
+        Frame {
+            elapsed: cycle / 2.0,
+            cycle,
+            time: 0.0,
+        }
+    }
+}
+
+/// Keeps track of time elapsed, and splits it into cycles.
+/// The animation loop in the browser feeds it timestamps
+/// (`advance`) so that the speed does not depend on the
+/// refresh rate, whereas the offline renderer steps it
+/// by a fixed amount (`tick`) to get the same frames every time.
+pub struct Timeline {
+    cycle: f64,
+    step: f64,
+    /// Timestamp last given to `advance`.
+    last: Option<f64>,
+    total: f64,
+    /// `total` at which the cycles are counted from.
+    origin: f64,
+    /// Which cycle the last frame was in.
+    /// `None` until the first frame.
+    index: Option<u64>,
+}
+
+impl Timeline {
+    /// `cycle` in milliseconds.
+    /// `tick` steps 60 frames per second.
+    pub fn new(cycle: f64) -> Timeline {
+        Timeline::fixed(cycle, 1000.0 / 60.0)
+    }
+
+    /// `tick` steps `step` milliseconds.
+    pub fn fixed(cycle: f64, step: f64) -> Timeline {
+        Timeline {
+            cycle,
+            step,
+            last: None,
+            total: 0.0,
+            origin: 0.0,
+            index: None,
+        }
+    }
+
+    pub fn cycle(&self) -> f64 {
+        self.cycle
+    }
+
+    /// A new cycle of `cycle` milliseconds begins with the next frame.
+    pub fn set_cycle(&mut self, cycle: f64) {
+        self.cycle = cycle;
+        self.origin = self.total;
+        self.index = None;
+    }
+
+    /// Number of frames `tick` produces in `cycles` cycles.
+    pub fn frames(&self, cycles: u32) -> u32 {
+        (cycles as f64 * self.cycle / self.step).ceil() as u32
+    }
+
+    /// Advances to the timestamp `now` (milliseconds,
+    /// such as the one `requestAnimationFrame` gives).
+    /// Returns the frame to draw, and `true` when a new cycle
+    /// has begun (meaning the points need to be `reset`).
+    pub fn advance(&mut self, now: f64) -> (Frame, bool) {
+        let delta = self.last.map_or(0.0, |last| (now - last).max(0.0));
+        self.last = Some(now);
+        self.advance_by(delta)
+    }
+
+    /// Same as `advance`, but by the fixed `step`.
+    pub fn tick(&mut self) -> (Frame, bool) {
+        let delta = if self.index.is_some() { self.step } else { 0.0 };
+        self.advance_by(delta)
+    }
+
+    /// Forgets the last timestamp, so that the time
+    /// spent while paused is not counted by `advance`.
+    pub fn pause(&mut self) {
+        self.last = None;
+    }
+
+    fn advance_by(&mut self, delta: f64) -> (Frame, bool) {
+        self.total += delta;
+        // When a frame takes longer than a cycle (e.g. the tab
+        // was in the background), cycles in between are skipped.
+        let since = self.total - self.origin;
+        let index = (since / self.cycle).floor() as u64;
+        let reset = self.index != Some(index);
+        self.index = Some(index);
+        let frame = Frame {
+            elapsed: since - index as f64 * self.cycle,
+            cycle: self.cycle,
+            time: self.total,
+        };
+        (frame, reset)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resets_every_cycle() {
+        let mut timeline = Timeline::fixed(100.0, 10.0);
+        let frames = timeline.frames(3);
+        assert_eq!(frames, 30);
+        let resets: Vec<f64> = (0..frames)
+            .map(|_| timeline.tick())
+            .filter(|(_, reset)| *reset)
+            .map(|(frame, _)| frame.elapsed)
+            .collect();
+        assert_eq!(resets, vec![0.0, 0.0, 0.0]);
+        assert!(timeline.tick().1);
+    }
+
+    #[test]
+    fn independent_of_frame_rate() {
+        let mut slow = Timeline::new(1000.0);
+        let mut fast = Timeline::new(1000.0);
+        for i in 0..=30 {
+            slow.advance(i as f64 * 1000.0 / 30.0);
+        }
+        for i in 0..=144 {
+            fast.advance(i as f64 * 1000.0 / 144.0);
+        }
+        let (a, _) = slow.advance(1250.0);
+        let (b, _) = fast.advance(1250.0);
+        assert!((a.elapsed - 250.0).abs() < 1e-6);
+        assert!((b.elapsed - 250.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn pause_skips_the_time() {
+        let mut timeline = Timeline::new(1000.0);
+        timeline.advance(0.0);
+        timeline.advance(100.0);
+        timeline.pause();
+        let (frame, reset) = timeline.advance(5000.0);
+        assert_eq!(frame.elapsed, 100.0);
+        assert!(!reset);
+    }
+
+    #[test]
+    fn cycle_changed_on_the_way() {
+        let mut timeline = Timeline::new(1000.0);
+        timeline.advance(0.0);
+        timeline.advance(400.0);
+        timeline.set_cycle(100.0);
+        let (frame, reset) = timeline.advance(450.0);
+        assert!(reset);
+        assert_eq!(
+            (frame.elapsed, frame.cycle, frame.time),
+            (50.0, 100.0, 450.0)
+        );
+        let (frame, reset) = timeline.advance(560.0);
+        assert!(reset);
+        assert_eq!(frame.elapsed, 60.0);
+    }
+}
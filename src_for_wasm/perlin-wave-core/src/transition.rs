@@ -0,0 +1,168 @@
+//! Blending from one `GraphType` to another
+//! instead of jumping when the graph type changes.
+
+use serde::{Deserialize, Serialize};
+
+use crate::easing::Easing;
+use crate::types::GraphType;
+
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum TransitionKind {
+    /// Jumps right away.
+    None,
+    /// Fades out the one and fades in the other.
+    Fade,
+    /// Turns the line of `Radio` into `Bars`, and `Bars`
+    /// into the ring of `Solar` (and back). Fades otherwise.
+    Morph,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct TransitionConfig {
+    #[serde(rename = "type")]
+    pub kind: TransitionKind,
+    /// In milliseconds.
+    pub duration: f64,
+    pub easing: Easing,
+}
+
+impl Default for TransitionConfig {
+    fn default() -> TransitionConfig {
+        TransitionConfig {
+            kind: TransitionKind::Fade,
+            duration: 300.0,
+            easing: Easing::Sine,
+        }
+    }
+}
+
+/// What to draw for a frame.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Blend {
+    Single(GraphType),
+    Between {
+        from: GraphType,
+        to: GraphType,
+        kind: TransitionKind,
+        /// From `0.0` (all `from`) to `1.0` (all `to`), eased.
+        progress: f64,
+    },
+}
+
+/// Follows the graph type shown,
+/// and tells how far the change has gone.
+pub struct Transition {
+    config: TransitionConfig,
+    current: GraphType,
+    /// The one changed from, and when.
+    from: Option<(GraphType, f64)>,
+}
+
+impl Transition {
+    pub fn new(config: TransitionConfig, graph_type: GraphType) -> Transition {
+        Transition {
+            config,
+            current: graph_type,
+            from: None,
+        }
+    }
+
+    /// `graph_type` is the one to be shown at `time` (milliseconds).
+    /// When it changes in the middle of a transition,
+    /// it starts over from what was being changed to.
+    pub fn update(&mut self, graph_type: GraphType, time: f64) -> Blend {
+        if graph_type != self.current {
+            self.from = match self.config.kind {
+                TransitionKind::None => None,
+                _ => Some((self.current, time)),
+            };
+            self.current = graph_type;
+        }
+
+        let (from, start) = match self.from {
+            Some(from) => from,
+            None => return Blend::Single(self.current),
+        };
+        let t = if self.config.duration > 0.0 {
+            (time - start) / self.config.duration
+        } else {
+            1.0
+        };
+        if t >= 1.0 {
+            self.from = None;
+            return Blend::Single(self.current);
+        }
+        Blend::Between {
+            from,
+            to: self.current,
+            kind: self.config.kind,
+            progress: self.config.easing.apply(t),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transition(kind: TransitionKind) -> Transition {
+        Transition::new(
+            TransitionConfig {
+                kind,
+                duration: 100.0,
+                easing: Easing::Linear,
+            },
+            GraphType::Radio,
+        )
+    }
+
+    #[test]
+    fn blends_for_the_duration() {
+        let mut t = transition(TransitionKind::Fade);
+        assert_eq!(
+            t.update(GraphType::Radio, 0.0),
+            Blend::Single(GraphType::Radio)
+        );
+        assert_eq!(
+            t.update(GraphType::Bars, 1000.0),
+            Blend::Between {
+                from: GraphType::Radio,
+                to: GraphType::Bars,
+                kind: TransitionKind::Fade,
+                progress: 0.0,
+            }
+        );
+        match t.update(GraphType::Bars, 1025.0) {
+            Blend::Between { progress, .. } => assert_eq!(progress, 0.25),
+            blend => panic!("{:?}", blend),
+        }
+        assert_eq!(
+            t.update(GraphType::Bars, 1100.0),
+            Blend::Single(GraphType::Bars)
+        );
+    }
+
+    #[test]
+    fn starts_over_when_changed_again() {
+        let mut t = transition(TransitionKind::Morph);
+        t.update(GraphType::Bars, 0.0);
+        match t.update(GraphType::Solar, 50.0) {
+            Blend::Between { from, progress, .. } => {
+                assert_eq!(from, GraphType::Bars);
+                assert_eq!(progress, 0.0);
+            }
+            blend => panic!("{:?}", blend),
+        }
+    }
+
+    #[test]
+    fn none_jumps() {
+        let mut t = transition(TransitionKind::None);
+        assert_eq!(
+            t.update(GraphType::Bars, 0.0),
+            Blend::Single(GraphType::Bars)
+        );
+    }
+}
@@ -0,0 +1,81 @@
+use log::debug;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Point {
+    pub x: f64,
+    pub y: f64,
+}
+
+impl Point {
+    pub fn normalize(&self) -> Point {
+        Point {
+            x: (self.x + 1.0) / 2.0,
+            y: (self.y + 1.0) / 2.0,
+        }
+    }
+}
+
+/// Visualization modes for the wave panel.
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum GraphType {
+    #[default]
+    Radio,
+    Bars,
+    Solar,
+    /// Filled below the line with a gradient.
+    Area,
+    /// The line and its reflection, like an oscilloscope.
+    Mirror,
+    /// Columns of dots, like an LED meter.
+    Dots,
+    /// Vertical lines from the bottom with dots on top.
+    Stems,
+}
+
+impl FromStr for GraphType {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "radio" => Ok(GraphType::Radio),
+            "bars" => Ok(GraphType::Bars),
+            "solar" => Ok(GraphType::Solar),
+            "area" => Ok(GraphType::Area),
+            "mirror" => Ok(GraphType::Mirror),
+            "dots" => Ok(GraphType::Dots),
+            "stems" => Ok(GraphType::Stems),
+            _ => Err(format!("No such graph type: {}", name)),
+        }
+    }
+}
+
+impl GraphType {
+    /// In the order `toggle` goes through.
+    pub const ALL: [GraphType; 7] = [
+        GraphType::Radio,
+        GraphType::Bars,
+        GraphType::Solar,
+        GraphType::Area,
+        GraphType::Mirror,
+        GraphType::Dots,
+        GraphType::Stems,
+    ];
+
+    pub fn toggle(&self) -> Self {
+        debug!("toggle[0]: {:?}", self);
+        let res = match self {
+            GraphType::Radio => GraphType::Bars,
+            GraphType::Bars => GraphType::Solar,
+            GraphType::Solar => GraphType::Area,
+            GraphType::Area => GraphType::Mirror,
+            GraphType::Mirror => GraphType::Dots,
+            GraphType::Dots => GraphType::Stems,
+            GraphType::Stems => GraphType::Radio,
+        };
+        debug!("toggle[1]: {:?}", res);
+        res
+    }
+}
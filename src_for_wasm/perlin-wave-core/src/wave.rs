@@ -0,0 +1,301 @@
+use lerp::Lerp;
+use log::info;
+use rand::rngs::StdRng;
+use rand::{self, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use crate::config::Config;
+use crate::constants::MAX_SEED;
+use crate::controls::Param;
+use crate::fractal::{Fractal, FractalConfig};
+use crate::perlin::{NoiseAlgorithm, NoiseSource, SeededNoise};
+use crate::types::Point;
+
+/// How `WavePanel` gets its points.
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum WaveMode {
+    /// Samples at a new offset every cycle,
+    /// and tweens from the previous points.
+    #[default]
+    Pulse,
+    /// Samples along the time axis (`Wave::flow`)
+    /// so that it scrolls and evolves continuously.
+    Flow,
+}
+
+/// For `WaveMode::Flow`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct FlowConfig {
+    /// Segments scrolled to the left per second.
+    pub speed: f64,
+    /// How far along the time axis of the noise per second.
+    /// The larger, the faster the shape changes.
+    pub evolution: f64,
+}
+
+impl Default for FlowConfig {
+    fn default() -> FlowConfig {
+        FlowConfig {
+            speed: 10.0,
+            evolution: 0.5,
+        }
+    }
+}
+
+/// Holds the points for the current cycle (`points`)
+/// and the ones for the previous (`points_prev`).
+/// Every time `reset` is called, it samples the noise
+/// at a new offset, and the current becomes the previous.
+pub struct Wave {
+    seed: u64,
+    segments: usize,
+    normal_width: f64,
+    amplitude: f64,
+    rng: StdRng,
+    algorithm: NoiseAlgorithm,
+    fractal: FractalConfig,
+    noise: Box<dyn NoiseSource>,
+    points: Vec<Point>,
+    points_prev: Vec<Point>,
+}
+
+fn noise(algorithm: NoiseAlgorithm, seed: u64, fractal: &FractalConfig) -> Box<dyn NoiseSource> {
+    Box::new(Fractal::new(
+        Box::new(SeededNoise::from_u64(algorithm, seed)),
+        fractal,
+    ))
+}
+
+impl Wave {
+    pub fn new(config: &Config) -> Wave {
+        // Unless explicitly given, we pick a seed at random.
+        // Either way, it is logged so that we can later
+        // reproduce the very same waves by passing it back.
+        let seed: u64 = config
+            .seed
+            .unwrap_or_else(|| rand::thread_rng().gen_range(0, MAX_SEED + 1));
+        info!("seed: {} ({:?})", seed, config.noise);
+
+        Wave {
+            seed,
+            segments: config.segments,
+            normal_width: config.normal_width,
+            amplitude: config.amplitude,
+            rng: StdRng::seed_from_u64(seed),
+            algorithm: config.noise,
+            fractal: config.fractal.clone(),
+            noise: noise(config.noise, seed, &config.fractal),
+            points: vec![],
+            points_prev: vec![],
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.points_prev = if !self.points.is_empty() {
+            self.points.clone()
+        } else {
+            vec![Point { x: 0.0, y: 0.0 }; self.segments]
+        };
+
+        self.points = vec![Point { x: 0.0, y: 0.0 }; self.segments];
+
+        let offset = self.rng.gen_range(0, 10) as f64;
+        for i in 0..self.segments {
+            let ratio = i as f64 / self.segments as f64;
+            let x: f64 = 0_f64.lerp(self.normal_width, ratio);
+            let nx: f64 = x + offset;
+            let y: f64 = self.sample(nx, offset);
+            self.points[i] = Point { x, y };
+        }
+    }
+
+    /// Points at `time` (milliseconds) for `WaveMode::Flow`.
+    /// Scrolling by a fraction of a segment is done by
+    /// blending neighbouring samples, since the samples
+    /// are too far apart for the noise to be smooth in between.
+    pub fn flow(&self, time: f64, flow: &FlowConfig) -> Vec<Point> {
+        let step = self.normal_width / self.segments as f64;
+        let scroll = flow.speed * time / 1000.0;
+        let whole = scroll.floor();
+        let frac = scroll - whole;
+        let t = flow.evolution * time / 1000.0;
+        let sample = |i: f64| self.sample(i * step, t);
+
+        (0..self.segments)
+            .map(|i| {
+                let k = i as f64 + whole;
+                Point {
+                    x: i as f64 * step,
+                    y: sample(k).lerp(sample(k + 1.0), frac),
+                }
+            })
+            .collect()
+    }
+
+    fn sample(&self, x: f64, y: f64) -> f64 {
+        (self.noise.get(x, y) * self.amplitude).clamp(-1.0, 1.0)
+    }
+
+    /// Changes one of the parameters while running.
+    /// The points change from the next cycle, except for
+    /// `Param::Segments` which starts over from flat.
+    /// `Param::Cycle` is not of the wave but of the timeline.
+    pub fn tune(&mut self, param: Param, value: f64) {
+        match param {
+            Param::Amplitude => self.amplitude = value,
+            Param::Frequency => self.fractal.frequency = value,
+            Param::Octaves => self.fractal.octaves = value.round().max(1.0) as usize,
+            Param::Segments => {
+                self.segments = value.round().max(2.0) as usize;
+                self.points.clear();
+                self.reset();
+            }
+            Param::Cycle => {}
+        }
+        if let Param::Frequency | Param::Octaves = param {
+            self.noise = noise(self.algorithm, self.seed, &self.fractal);
+        }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn algorithm(&self) -> NoiseAlgorithm {
+        self.algorithm
+    }
+
+    pub fn points(&self) -> &[Point] {
+        &self.points
+    }
+
+    pub fn points_prev(&self) -> &[Point] {
+        &self.points_prev
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::{NORMAL_WIDTH, SEGMENTS};
+    use crate::theme::ThemeSpec;
+
+    fn config(seed: u64) -> Config {
+        Config {
+            theme: ThemeSpec::default(),
+            bgcolor: None,
+            seed: Some(seed),
+            noise: NoiseAlgorithm::default(),
+            fractal: FractalConfig::default(),
+            cycle: 350.0,
+            step: None,
+            segments: SEGMENTS,
+            normal_width: NORMAL_WIDTH,
+            amplitude: 1.0,
+            panels: vec![],
+        }
+    }
+
+    #[test]
+    fn same_seed_same_points() {
+        let mut a = Wave::new(&config(7));
+        let mut b = Wave::new(&config(7));
+        for _ in 0..3 {
+            a.reset();
+            b.reset();
+            assert_eq!(a.points(), b.points());
+            assert_eq!(a.points_prev(), b.points_prev());
+        }
+    }
+
+    #[test]
+    fn different_seed_different_points() {
+        let mut a = Wave::new(&config(7));
+        let mut b = Wave::new(&config(8));
+        a.reset();
+        b.reset();
+        assert_ne!(a.points(), b.points());
+    }
+
+    #[test]
+    fn upper_bits_of_the_seed_count() {
+        let flow = FlowConfig::default();
+        let a = Wave::new(&config(7));
+        let b = Wave::new(&config(7 + (1 << 32)));
+        assert_ne!(a.flow(0.0, &flow), b.flow(0.0, &flow));
+    }
+
+    #[test]
+    fn random_seed_fits_in_a_js_number() {
+        let config = Config {
+            seed: None,
+            ..config(0)
+        };
+        for _ in 0..100 {
+            assert!(Wave::new(&config).seed() <= MAX_SEED);
+        }
+    }
+
+    #[test]
+    fn previous_points_follow() {
+        let mut wave = Wave::new(&config(7));
+        wave.reset();
+        assert_eq!(wave.points().len(), SEGMENTS);
+        assert!(wave.points_prev().iter().all(|p| p.y == 0.0));
+
+        let points = wave.points().to_vec();
+        wave.reset();
+        assert_eq!(wave.points_prev(), &points[..]);
+    }
+
+    #[test]
+    fn flow_scrolls_to_the_left() {
+        let wave = Wave::new(&config(7));
+        let flow = FlowConfig {
+            speed: 10.0,
+            evolution: 0.0,
+        };
+        let before = wave.flow(0.0, &flow);
+        let after = wave.flow(100.0, &flow);
+        assert_eq!(after.len(), SEGMENTS);
+        assert_eq!(before[1].y, after[0].y);
+        assert_eq!(before[SEGMENTS - 1].y, after[SEGMENTS - 2].y);
+        assert_ne!(wave.flow(50.0, &flow)[0].y, before[0].y);
+    }
+
+    #[test]
+    fn segments_from_config() {
+        let mut wave = Wave::new(&Config {
+            segments: 40,
+            ..config(7)
+        });
+        wave.reset();
+        assert_eq!(wave.points().len(), 40);
+        assert_eq!(wave.points_prev().len(), 40);
+    }
+
+    #[test]
+    fn tuned_while_running() {
+        let mut wave = Wave::new(&config(7));
+        wave.reset();
+        wave.tune(Param::Segments, 20.0);
+        assert_eq!(wave.points().len(), 20);
+        assert!(wave.points_prev().iter().all(|p| p.y == 0.0));
+
+        let mut louder = Wave::new(&config(7));
+        louder.tune(Param::Amplitude, 100.0);
+        let flow = FlowConfig::default();
+        let points = louder.flow(0.0, &flow);
+        assert!(points.iter().all(|p| p.y.abs() <= 1.0));
+        assert!(points.iter().any(|p| p.y.abs() == 1.0));
+
+        let mut finer = Wave::new(&config(7));
+        finer.tune(Param::Frequency, 3.0);
+        assert_ne!(
+            finer.flow(0.0, &flow),
+            Wave::new(&config(7)).flow(0.0, &flow)
+        );
+    }
+}
@@ -0,0 +1,171 @@
+use std::convert::TryFrom;
+use std::fs::{self, File};
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use crate::raster::PixmapSurface;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Format {
+    /// One PNG file per frame, in a directory or
+    /// numbered after the file name ending with `.png`.
+    Png,
+    Gif,
+    Apng,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "png" => Ok(Format::Png),
+            "gif" => Ok(Format::Gif),
+            "apng" => Ok(Format::Apng),
+            _ => Err(format!("No such format: {}", name)),
+        }
+    }
+}
+
+impl Format {
+    /// Guesses from the extension of `out`.
+    /// When it has none of them, `out` is
+    /// regarded as a directory for PNG files.
+    pub fn guess(out: &Path) -> Format {
+        match out.extension().and_then(|ext| ext.to_str()) {
+            Some("gif") => Format::Gif,
+            Some("apng") => Format::Apng,
+            _ => Format::Png,
+        }
+    }
+}
+
+/// GIF (and the frame delay of APNG) can not go beyond 16 bits.
+fn to_u16(name: &str, value: u32) -> Result<u16, String> {
+    u16::try_from(value)
+        .map_err(|_| format!("{} must be at most {}, got {}", name, u16::MAX, value))
+}
+
+/// Writes frames one by one to whichever format.
+pub enum Encoder {
+    /// Writes `{prefix}{index}.png` in `dir`.
+    Png {
+        dir: PathBuf,
+        prefix: String,
+        index: usize,
+    },
+    Gif(gif::Encoder<BufWriter<File>>),
+    Apng(png::Writer<BufWriter<File>>),
+}
+
+impl Encoder {
+    pub fn new(
+        format: Format,
+        out: &Path,
+        width: u32,
+        height: u32,
+        frames: u32,
+        fps: u32,
+    ) -> Result<Encoder, String> {
+        match format {
+            Format::Png => {
+                // `wave.png` gives `wave-0000.png`, `wave-0001.png`, ...
+                let (dir, prefix) = match (out.extension(), out.file_stem()) {
+                    (Some(ext), Some(stem)) if ext == "png" => (
+                        out.parent().unwrap_or_else(|| Path::new("")).to_path_buf(),
+                        format!("{}-", stem.to_string_lossy()),
+                    ),
+                    _ => (out.to_path_buf(), "frame-".into()),
+                };
+                if !dir.as_os_str().is_empty() {
+                    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+                }
+                Ok(Encoder::Png {
+                    dir,
+                    prefix,
+                    index: 0,
+                })
+            }
+            Format::Gif => {
+                let (width, height) = (to_u16("width", width)?, to_u16("height", height)?);
+                let file = BufWriter::new(File::create(out).map_err(|e| e.to_string())?);
+                let mut encoder =
+                    gif::Encoder::new(file, width, height, &[]).map_err(|e| e.to_string())?;
+                encoder
+                    .set_repeat(gif::Repeat::Infinite)
+                    .map_err(|e| e.to_string())?;
+                Ok(Encoder::Gif(encoder))
+            }
+            Format::Apng => {
+                let file = BufWriter::new(File::create(out).map_err(|e| e.to_string())?);
+                let mut encoder = png::Encoder::new(file, width, height);
+                encoder.set_color(png::ColorType::Rgba);
+                encoder.set_depth(png::BitDepth::Eight);
+                encoder.set_animated(frames, 0).map_err(|e| e.to_string())?;
+                encoder
+                    .set_frame_delay(1, to_u16("fps", fps)?)
+                    .map_err(|e| e.to_string())?;
+                Ok(Encoder::Apng(
+                    encoder.write_header().map_err(|e| e.to_string())?,
+                ))
+            }
+        }
+    }
+
+    pub fn write(&mut self, surface: &PixmapSurface, fps: u32) -> Result<(), String> {
+        match self {
+            Encoder::Png { dir, prefix, index } => {
+                let path = dir.join(format!("{}{:04}.png", prefix, index));
+                *index += 1;
+                surface.pixmap().save_png(path).map_err(|e| e.to_string())
+            }
+            Encoder::Gif(encoder) => {
+                let pixmap = surface.pixmap();
+                let mut rgba = surface.rgba();
+                let mut frame = gif::Frame::from_rgba_speed(
+                    to_u16("width", pixmap.width())?,
+                    to_u16("height", pixmap.height())?,
+                    &mut rgba,
+                    10,
+                );
+                // GIF counts the delay in 1/100 seconds.
+                frame.delay = (100.0 / fps as f64).round().max(1.0) as u16;
+                encoder.write_frame(&frame).map_err(|e| e.to_string())
+            }
+            Encoder::Apng(writer) => writer
+                .write_image_data(&surface.rgba())
+                .map_err(|e| e.to_string()),
+        }
+    }
+
+    pub fn finish(self) -> Result<(), String> {
+        match self {
+            Encoder::Apng(writer) => writer.finish().map_err(|e| e.to_string()),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn guessed_from_the_extension() {
+        assert_eq!(Format::guess(Path::new("wave.png")), Format::Png);
+        assert_eq!(Format::guess(Path::new("wave.apng")), Format::Apng);
+        assert_eq!(Format::guess(Path::new("wave.gif")), Format::Gif);
+        assert_eq!(Format::guess(Path::new("frames/")), Format::Png);
+    }
+
+    #[test]
+    fn too_large_for_gif() {
+        let out = std::env::temp_dir().join("perlin-wave-too-large.gif");
+        let err = Encoder::new(Format::Gif, &out, 70_000, 10, 1, 60)
+            .err()
+            .unwrap();
+        assert_eq!(err, "width must be at most 65535, got 70000");
+        assert!(!out.exists());
+    }
+}
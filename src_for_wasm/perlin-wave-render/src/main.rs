@@ -0,0 +1,342 @@
+//! Renders the wave panel without a browser.
+//! Runs the same loop as `start_app` in `perlin-wave`,
+//! and writes the frames as PNG files, GIF, or APNG.
+
+mod encode;
+mod raster;
+
+use serde::de::{value::StrDeserializer, IntoDeserializer};
+use serde::Deserialize;
+use std::fs;
+use std::path::PathBuf;
+use std::process;
+
+use perlin_wave_core::config::Config;
+use perlin_wave_core::constants::{CYCLE_MS, NORMAL_WIDTH, SEGMENTS};
+use perlin_wave_core::easing::{Easing, EasingConfig};
+use perlin_wave_core::fractal::FractalConfig;
+use perlin_wave_core::graphics::wave::WaveGraphics;
+use perlin_wave_core::graphics::Graphics;
+use perlin_wave_core::palette::ColorConfig;
+use perlin_wave_core::spline::CurveConfig;
+use perlin_wave_core::theme::{Theme, ThemeName, ThemeSpec};
+use perlin_wave_core::timeline::{Frame, Timeline};
+use perlin_wave_core::types::GraphType;
+use perlin_wave_core::wave::{FlowConfig, Wave, WaveMode};
+
+use crate::encode::{Encoder, Format};
+use crate::raster::PixmapSurface;
+
+const HELP: &str = "\
+perlin-wave-render
+
+USAGE:
+  perlin-wave-render [OPTIONS] <OUTPUT>
+
+  OUTPUT ending with `.gif` writes GIF, `.apng` writes APNG,
+  `.png` writes PNG files numbered after it (`wave-0000.png`),
+  and anything else is a directory for PNG files.
+
+OPTIONS:
+  --width <px>        [default: 550]
+  --height <px>       [default: 183]
+  --seed <n>          [default: random]
+  --graph <type>      radio, bars, solar, area, mirror, dots, or stems
+                      [default: radio]
+  --noise <name>      perlin, simplex, supersimplex, value, worley, or opensimplex
+  --fractal <type>    none, fbm, ridged, billow, or turbulence
+  --easing <easing>   linear, quad, cubic, quart, sine, expo, elastic, bounce,
+                      back, or cubic-bezier(x1, y1, x2, y2)
+                      [default: quad for radio, area, and mirror,
+                      and linear for the others]
+  --curve <type>      linear, catmull-rom, monotone, or quadratic
+                      for radio, area, and mirror [default: linear]
+  --tension <n>       between 0 and 1 for the curve [default: 0]
+  --palette <name>    viridis, magma, plasma, or inferno [default: none]
+  --gradient <type>   linear or radial, for the lines with a palette
+                      [default: linear]
+  --mode <mode>       pulse or flow [default: pulse]
+  --speed <n>         segments scrolled per second in flow [default: 10]
+  --evolution <n>     how fast the shape changes in flow [default: 0.5]
+  --cycle <ms>        length of a cycle [default: 350]
+  --segments <n>      points sampled from the noise [default: 110]
+  --amplitude <x>     multiplies the points [default: 1]
+  --cycles <n>        [default: 3]
+  --fps <n>           [default: 60]
+  --format <format>   png, gif, or apng [default: guessed from OUTPUT]
+  --theme <theme>     dark, light, high-contrast, or a JSON file
+                      [default: dark]
+  --bgcolor <color>   overrides the background of the theme
+  --color <color>     overrides the primary color of the theme
+  --font <file>       TTF or OTF for the text [default: Hack]
+  -h, --help
+";
+
+struct Args {
+    width: u32,
+    height: u32,
+    graph_type: GraphType,
+    easing: Option<Easing>,
+    curve: CurveConfig,
+    colors: ColorConfig,
+    /// `Some` for `WaveMode::Flow`.
+    flow: Option<FlowConfig>,
+    cycles: u32,
+    fps: u32,
+    format: Format,
+    theme: Theme,
+    /// Read into `PixmapSurface` instead of the built-in font.
+    font: Option<PathBuf>,
+    config: Config,
+    out: PathBuf,
+}
+
+/// For enums in `Config` which only know their names through serde.
+fn from_name<T: for<'de> Deserialize<'de>>(name: &str) -> Result<T, String> {
+    let de: StrDeserializer<serde::de::value::Error> = name.into_deserializer();
+    T::deserialize(de).map_err(|e| e.to_string())
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut args = pico_args::Arguments::from_env();
+
+    if args.contains(["-h", "--help"]) {
+        print!("{}", HELP);
+        process::exit(0);
+    }
+
+    let err = |e: pico_args::Error| e.to_string();
+
+    let theme = match args
+        .opt_value_from_str::<_, String>("--theme")
+        .map_err(err)?
+    {
+        Some(name) => match name.parse::<ThemeName>() {
+            Ok(name) => ThemeSpec::Named(name),
+            Err(_) => {
+                let json = fs::read_to_string(&name).map_err(|e| format!("{}: {}", name, e))?;
+                let mut de = serde_json::Deserializer::from_str(&json);
+                ThemeSpec::Custom(ThemeSpec::from_deserializer(&mut de).map_err(|e| e.to_string())?)
+            }
+        },
+        None => ThemeSpec::default(),
+    };
+
+    let mut fractal = FractalConfig::default();
+    if let Some(name) = args
+        .opt_value_from_str::<_, String>("--fractal")
+        .map_err(err)?
+    {
+        fractal.kind = from_name(&name)?;
+    }
+
+    let config = Config {
+        theme,
+        bgcolor: args.opt_value_from_str("--bgcolor").map_err(err)?,
+        seed: args.opt_value_from_str("--seed").map_err(err)?,
+        noise: match args
+            .opt_value_from_str::<_, String>("--noise")
+            .map_err(err)?
+        {
+            Some(name) => from_name(&name)?,
+            None => Default::default(),
+        },
+        fractal,
+        cycle: args
+            .opt_value_from_str("--cycle")
+            .map_err(err)?
+            .unwrap_or(CYCLE_MS),
+        step: None,
+        segments: args
+            .opt_value_from_str("--segments")
+            .map_err(err)?
+            .unwrap_or(SEGMENTS),
+        normal_width: NORMAL_WIDTH,
+        amplitude: args
+            .opt_value_from_str("--amplitude")
+            .map_err(err)?
+            .unwrap_or(1.0),
+        panels: vec![],
+    };
+    config.validate().map_err(|e| e.to_string())?;
+
+    let mode: WaveMode = match args
+        .opt_value_from_str::<_, String>("--mode")
+        .map_err(err)?
+    {
+        Some(name) => from_name(&name)?,
+        None => Default::default(),
+    };
+    let mut flow = FlowConfig::default();
+    if let Some(speed) = args.opt_value_from_str("--speed").map_err(err)? {
+        flow.speed = speed;
+    }
+    if let Some(evolution) = args.opt_value_from_str("--evolution").map_err(err)? {
+        flow.evolution = evolution;
+    }
+    if !(flow.speed >= 0.0 && flow.evolution >= 0.0) {
+        return Err("--speed and --evolution must not be negative".into());
+    }
+
+    let mut curve = CurveConfig::default();
+    if let Some(kind) = args.opt_value_from_str("--curve").map_err(err)? {
+        curve.kind = kind;
+    }
+    if let Some(tension) = args
+        .opt_value_from_str::<_, f64>("--tension")
+        .map_err(err)?
+    {
+        if !(0.0..=1.0).contains(&tension) {
+            return Err("--tension must be between 0 and 1".into());
+        }
+        curve.tension = tension;
+    }
+
+    let mut colors = ColorConfig {
+        palette: args.opt_value_from_str("--palette").map_err(err)?,
+        ..ColorConfig::default()
+    };
+    if let Some(gradient) = args.opt_value_from_str("--gradient").map_err(err)? {
+        colors.gradient = gradient;
+    }
+
+    let mut theme = config.theme();
+    if let Some(color) = args.opt_value_from_str("--color").map_err(err)? {
+        theme.primary = color;
+    }
+
+    let format: Option<Format> = args.opt_value_from_str("--format").map_err(err)?;
+
+    let mut parsed = Args {
+        width: args
+            .opt_value_from_str("--width")
+            .map_err(err)?
+            .unwrap_or(550),
+        height: args
+            .opt_value_from_str("--height")
+            .map_err(err)?
+            .unwrap_or(183),
+        graph_type: args
+            .opt_value_from_str("--graph")
+            .map_err(err)?
+            .unwrap_or(GraphType::Radio),
+        easing: args.opt_value_from_str("--easing").map_err(err)?,
+        curve,
+        colors,
+        flow: match mode {
+            WaveMode::Pulse => None,
+            WaveMode::Flow => Some(flow),
+        },
+        cycles: args
+            .opt_value_from_str("--cycles")
+            .map_err(err)?
+            .unwrap_or(3),
+        fps: args.opt_value_from_str("--fps").map_err(err)?.unwrap_or(60),
+        format: Format::Png,
+        theme,
+        font: args.opt_value_from_str("--font").map_err(err)?,
+        config,
+        out: args.free_from_str().map_err(err)?,
+    };
+
+    let rest = args.finish();
+    if !rest.is_empty() {
+        return Err(format!("Unknown arguments: {:?}", rest));
+    }
+
+    if parsed.fps == 0 {
+        return Err("--fps must be positive".into());
+    }
+
+    parsed.format = format.unwrap_or_else(|| Format::guess(&parsed.out));
+    Ok(parsed)
+}
+
+fn render(args: &Args) -> Result<(), String> {
+    // Stepping by a fixed amount instead of the actual time
+    // spent so that the same frames are produced every time.
+    let mut timeline = Timeline::fixed(args.config.cycle, 1000.0 / args.fps as f64);
+    let frames = timeline.frames(args.cycles);
+    let mut surface = PixmapSurface::new(args.width, args.height)?;
+    if let Some(path) = &args.font {
+        let data = fs::read(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        surface.set_font_data(data)?;
+    }
+    let mut g = WaveGraphics::new(
+        Box::new(surface),
+        args.width as f64,
+        args.height as f64,
+        &args.theme.background,
+        &args.theme.primary,
+    );
+    g.set_theme(&args.theme);
+    if let Some(easing) = args.easing {
+        let mut config = EasingConfig::default();
+        match args.graph_type {
+            GraphType::Radio => config.radio = easing,
+            GraphType::Bars => config.bars = easing,
+            GraphType::Solar => config.solar = easing,
+            GraphType::Area => config.area = easing,
+            GraphType::Mirror => config.mirror = easing,
+            GraphType::Dots => config.dots = easing,
+            GraphType::Stems => config.stems = easing,
+        }
+        g.set_easing(config);
+    }
+    g.set_curve(args.curve.clone());
+    g.set_colors(args.colors.clone());
+    let mut encoder = Encoder::new(
+        args.format,
+        &args.out,
+        args.width,
+        args.height,
+        frames,
+        args.fps,
+    )?;
+
+    let mut wave = Wave::new(&args.config);
+
+    for _ in 0..frames {
+        let (frame, reset) = timeline.tick();
+        if reset {
+            wave.reset();
+        }
+        g.clear();
+        match &args.flow {
+            Some(flow) => {
+                let points = wave.flow(frame.time, flow);
+                g.render(
+                    args.graph_type,
+                    &points,
+                    &points,
+                    Frame::middle(frame.cycle),
+                );
+            }
+            None => g.render(args.graph_type, wave.points(), wave.points_prev(), frame),
+        }
+
+        let surface = g
+            .surface()
+            .as_any()
+            .downcast_ref::<PixmapSurface>()
+            .ok_or("Failed to downcast_ref::<PixmapSurface>()")?;
+        encoder.write(surface, args.fps)?;
+    }
+
+    encoder.finish()?;
+    println!(
+        "{} frames (seed: {}) -> {}",
+        frames,
+        wave.seed(),
+        args.out.display()
+    );
+    Ok(())
+}
+
+fn main() {
+    let result = parse_args().and_then(|args| render(&args));
+    if let Err(err) = result {
+        eprintln!("Error: {}", err);
+        process::exit(1);
+    }
+}
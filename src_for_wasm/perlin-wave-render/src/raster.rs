@@ -0,0 +1,471 @@
+use ab_glyph::{point, Font, FontArc, PxScale, ScaleFont};
+use std::any::Any;
+use tiny_skia::{
+    Color, FillRule, GradientStop, LineCap, LineJoin, LinearGradient, Mask, Paint, Path,
+    PathBuilder, Pixmap, Point, RadialGradient, Rect, SpreadMode, Stroke, Transform,
+};
+
+use perlin_wave_core::color::Rgba;
+use perlin_wave_core::spline;
+use perlin_wave_core::surface::{Gradient, GradientShape, Matrix, Surface};
+
+/// For either filling or stroking.
+#[derive(Clone)]
+enum Style {
+    Color(Rgba),
+    /// With the points already transformed.
+    Gradient(Gradient),
+}
+
+#[derive(Clone)]
+struct State {
+    matrix: Matrix,
+    fill: Style,
+    stroke: Style,
+    alpha: f64,
+    line_width: f64,
+    /// Blur and color of the glow.
+    shadow: Option<(f64, Rgba)>,
+    /// In pixels, taken from the CSS font given to `set_font`.
+    font_size: f64,
+}
+
+impl Default for State {
+    fn default() -> Self {
+        State {
+            matrix: Matrix::IDENTITY,
+            fill: Style::Color(Rgba::BLACK),
+            stroke: Style::Color(Rgba::BLACK),
+            alpha: 1.0,
+            line_width: 1.0,
+            shadow: None,
+            // The default of canvas (`10px sans-serif`).
+            font_size: 10.0,
+        }
+    }
+}
+
+/// `Surface` rasterizing in software with `tiny-skia`.
+/// Text is drawn with one font whatever the family given
+/// to `set_font`: Hack unless another is set with `set_font_data`.
+pub struct PixmapSurface {
+    pixmap: Pixmap,
+    font: FontArc,
+    state: State,
+    stack: Vec<State>,
+    path: PathBuilder,
+}
+
+impl PixmapSurface {
+    pub fn new(width: u32, height: u32) -> Result<PixmapSurface, String> {
+        let pixmap = Pixmap::new(width, height)
+            .ok_or_else(|| format!("Invalid size: {} x {}", width, height))?;
+        let font = FontArc::try_from_slice(epaint_default_fonts::HACK_REGULAR)
+            .map_err(|e| e.to_string())?;
+        Ok(PixmapSurface {
+            pixmap,
+            font,
+            state: State::default(),
+            stack: vec![],
+            path: PathBuilder::new(),
+        })
+    }
+
+    /// `tiny-skia` can't blur, so the glow is made of
+    /// wider and fainter strokes around the shape.
+    fn glow(&mut self, path: &Path, transform: Transform) {
+        let (blur, rgba) = match self.state.shadow {
+            Some(shadow) => shadow,
+            None => return,
+        };
+        let paint = paint(
+            Rgba {
+                a: rgba.a * 0.2,
+                ..rgba
+            },
+            self.state.alpha,
+        );
+        for i in 1..=3 {
+            let stroke = Stroke {
+                width: (self.state.line_width + blur * i as f64 / 1.5) as f32,
+                line_cap: LineCap::Round,
+                line_join: LineJoin::Round,
+                ..Stroke::default()
+            };
+            self.pixmap
+                .stroke_path(path, &paint, &stroke, transform, None);
+        }
+    }
+
+    /// Draws the text with the font of `data` (TTF or OTF).
+    pub fn set_font_data(&mut self, data: Vec<u8>) -> Result<(), String> {
+        self.font = FontArc::try_from_vec(data).map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
+    pub fn pixmap(&self) -> &Pixmap {
+        &self.pixmap
+    }
+
+    /// Pixels as (not premultiplied) RGBA.
+    pub fn rgba(&self) -> Vec<u8> {
+        self.pixmap
+            .pixels()
+            .iter()
+            .flat_map(|p| {
+                let c = p.demultiply();
+                vec![c.red(), c.green(), c.blue(), c.alpha()]
+            })
+            .collect()
+    }
+}
+
+fn color(color: Rgba) -> Color {
+    Color::from_rgba8(color.r, color.g, color.b, (color.a * 255.0).round() as u8)
+}
+
+fn paint(rgba: Rgba, alpha: f64) -> Paint<'static> {
+    let mut paint = Paint::default();
+    paint.set_color(color(Rgba {
+        a: rgba.a * alpha,
+        ..rgba
+    }));
+    paint.anti_alias = true;
+    paint
+}
+
+/// `transform` is the one the shape is drawn with.
+/// Since the gradient is already transformed,
+/// it is cancelled out for the gradient.
+fn style_paint(style: &Style, alpha: f64, transform: Transform) -> Paint<'static> {
+    let gradient = match style {
+        Style::Color(rgba) => return paint(*rgba, alpha),
+        Style::Gradient(gradient) => gradient,
+    };
+    let stops: Vec<(f64, String)> = gradient.stops_from_center();
+    let first = stops.first().map_or(Rgba::BLACK, |(_, c)| parse(c));
+    let stops: Vec<GradientStop> = stops
+        .iter()
+        .map(|(offset, c)| GradientStop::new(*offset as f32, color(parse(c))))
+        .collect();
+    let inverse = transform.invert().unwrap_or_default();
+    let shader = match gradient.shape {
+        GradientShape::Linear { x0, y0, x1, y1 } => LinearGradient::new(
+            Point::from_xy(x0 as f32, y0 as f32),
+            Point::from_xy(x1 as f32, y1 as f32),
+            stops,
+            SpreadMode::Pad,
+            inverse,
+        ),
+        GradientShape::Radial { x, y, r1, .. } => {
+            let center = Point::from_xy(x as f32, y as f32);
+            RadialGradient::new(center, center, r1 as f32, stops, SpreadMode::Pad, inverse)
+        }
+    };
+    match shader {
+        Some(mut shader) => {
+            shader.apply_opacity(alpha as f32);
+            Paint {
+                shader,
+                anti_alias: true,
+                ..Paint::default()
+            }
+        }
+        None => paint(first, alpha),
+    }
+}
+
+fn parse(color: &str) -> Rgba {
+    Rgba::parse(color).unwrap_or(Rgba::BLACK)
+}
+
+impl Surface for PixmapSurface {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn clear(&mut self) {
+        self.pixmap.fill(Color::TRANSPARENT);
+    }
+
+    fn save(&mut self) {
+        self.stack.push(self.state.clone());
+    }
+
+    fn restore(&mut self) {
+        if let Some(state) = self.stack.pop() {
+            self.state = state;
+        }
+    }
+
+    fn translate(&mut self, x: f64, y: f64) {
+        self.state.matrix = self.state.matrix.translate(x, y);
+    }
+
+    fn rotate(&mut self, angle: f64) {
+        self.state.matrix = self.state.matrix.rotate(angle);
+    }
+
+    fn set_global_alpha(&mut self, alpha: f64) {
+        self.state.alpha = alpha.clamp(0.0, 1.0);
+    }
+
+    fn set_fill_style(&mut self, color: &str) {
+        self.state.fill = Style::Color(parse(color));
+    }
+
+    fn set_fill_gradient(&mut self, gradient: &Gradient) {
+        self.state.fill = Style::Gradient(gradient.transform(&self.state.matrix));
+    }
+
+    fn set_stroke_style(&mut self, color: &str) {
+        self.state.stroke = Style::Color(parse(color));
+    }
+
+    fn set_stroke_gradient(&mut self, gradient: &Gradient) {
+        self.state.stroke = Style::Gradient(gradient.transform(&self.state.matrix));
+    }
+
+    /// Only the size such as `16px` is taken.
+    fn set_font(&mut self, font: &str) {
+        if let Some(size) = font
+            .split_whitespace()
+            .find_map(|word| word.strip_suffix("px")?.parse::<f64>().ok())
+        {
+            self.state.font_size = size;
+        }
+    }
+
+    fn set_line_width(&mut self, width: f64) {
+        self.state.line_width = width;
+    }
+
+    fn set_shadow(&mut self, blur: f64, color: &str) {
+        self.state.shadow = if blur > 0.0 {
+            Some((blur, parse(color)))
+        } else {
+            None
+        };
+    }
+
+    fn begin_path(&mut self) {
+        self.path = PathBuilder::new();
+    }
+
+    // Just like canvas, path coordinates are
+    // transformed at the time they are added.
+    fn move_to(&mut self, x: f64, y: f64) {
+        let (x, y) = self.state.matrix.apply(x, y);
+        self.path.move_to(x as f32, y as f32);
+    }
+
+    fn line_to(&mut self, x: f64, y: f64) {
+        let (x, y) = self.state.matrix.apply(x, y);
+        self.path.line_to(x as f32, y as f32);
+    }
+
+    fn bezier_curve_to(&mut self, c1x: f64, c1y: f64, c2x: f64, c2y: f64, x: f64, y: f64) {
+        let m = self.state.matrix;
+        let (c1x, c1y) = m.apply(c1x, c1y);
+        let (c2x, c2y) = m.apply(c2x, c2y);
+        let (x, y) = m.apply(x, y);
+        self.path.cubic_to(
+            c1x as f32, c1y as f32, c2x as f32, c2y as f32, x as f32, y as f32,
+        );
+    }
+
+    fn arc(&mut self, x: f64, y: f64, radius: f64, start: f64, end: f64) {
+        let ((x0, y0), beziers) = spline::arc(x, y, radius, start, end);
+        if self.path.is_empty() {
+            self.move_to(x0, y0);
+        } else {
+            self.line_to(x0, y0);
+        }
+        for b in beziers {
+            self.bezier_curve_to(b.c1.0, b.c1.1, b.c2.0, b.c2.1, b.to.0, b.to.1);
+        }
+    }
+
+    fn close_path(&mut self) {
+        self.path.close();
+    }
+
+    fn stroke(&mut self) {
+        if let Some(path) = self.path.clone().finish() {
+            self.glow(&path, Transform::identity());
+            self.pixmap.stroke_path(
+                &path,
+                &style_paint(&self.state.stroke, self.state.alpha, Transform::identity()),
+                &Stroke {
+                    width: self.state.line_width as f32,
+                    ..Stroke::default()
+                },
+                Transform::identity(),
+                None,
+            );
+        }
+    }
+
+    fn fill(&mut self) {
+        if let Some(path) = self.path.clone().finish() {
+            self.glow(&path, Transform::identity());
+            self.pixmap.fill_path(
+                &path,
+                &style_paint(&self.state.fill, self.state.alpha, Transform::identity()),
+                FillRule::Winding,
+                Transform::identity(),
+                None,
+            );
+        }
+    }
+
+    fn fill_rect(&mut self, x: f64, y: f64, width: f64, height: f64) {
+        let (x, width) = if width < 0.0 {
+            (x + width, -width)
+        } else {
+            (x, width)
+        };
+        let (y, height) = if height < 0.0 {
+            (y + height, -height)
+        } else {
+            (y, height)
+        };
+        let m = self.state.matrix;
+        let transform = Transform::from_row(
+            m.a as f32, m.b as f32, m.c as f32, m.d as f32, m.e as f32, m.f as f32,
+        );
+        if let Some(rect) = Rect::from_xywh(x as f32, y as f32, width as f32, height as f32) {
+            self.glow(&PathBuilder::from_rect(rect), transform);
+            self.pixmap.fill_rect(
+                rect,
+                &style_paint(&self.state.fill, self.state.alpha, transform),
+                transform,
+                None,
+            );
+        }
+    }
+
+    /// `(x, y)` is on the baseline at the left, and it is moved by
+    /// the transform, but the glyphs are neither scaled nor rotated.
+    fn fill_text(&mut self, text: &str, x: f64, y: f64) {
+        let (x, y) = self.state.matrix.apply(x, y);
+        let (width, height) = (self.pixmap.width(), self.pixmap.height());
+        let mut mask = match Mask::new(width, height) {
+            Some(mask) => mask,
+            None => return,
+        };
+        let font = self
+            .font
+            .as_scaled(PxScale::from(self.state.font_size as f32));
+        let mut caret = x as f32;
+        let mut prev = None;
+        for c in text.chars() {
+            let id = font.glyph_id(c);
+            if let Some(prev) = prev {
+                caret += font.kern(prev, id);
+            }
+            prev = Some(id);
+            let glyph = id.with_scale_and_position(font.scale(), point(caret, y as f32));
+            caret += font.h_advance(id);
+            let outlined = match font.outline_glyph(glyph) {
+                Some(outlined) => outlined,
+                None => continue,
+            };
+            let bounds = outlined.px_bounds();
+            let data = mask.data_mut();
+            outlined.draw(|gx, gy, coverage| {
+                let px = bounds.min.x as i64 + gx as i64;
+                let py = bounds.min.y as i64 + gy as i64;
+                if px < 0 || py < 0 || px >= width as i64 || py >= height as i64 {
+                    return;
+                }
+                let i = py as usize * width as usize + px as usize;
+                let value = (coverage.clamp(0.0, 1.0) * 255.0).round() as u8;
+                data[i] = data[i].max(value);
+            });
+        }
+        if let Some(rect) = Rect::from_xywh(0.0, 0.0, width as f32, height as f32) {
+            self.pixmap.fill_rect(
+                rect,
+                &style_paint(&self.state.fill, self.state.alpha, Transform::identity()),
+                Transform::identity(),
+                Some(&mask),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fill_rect_paints() {
+        let mut s = PixmapSurface::new(4, 4).unwrap();
+        s.set_fill_style("#ff0000");
+        s.fill_rect(0.0, 4.0, 2.0, -2.0);
+        let rgba = s.rgba();
+        let at = |x: usize, y: usize| &rgba[(y * 4 + x) * 4..(y * 4 + x) * 4 + 4];
+        assert_eq!(at(0, 3), &[255, 0, 0, 255]);
+        assert_eq!(at(3, 0), &[0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn glows_around() {
+        let mut s = PixmapSurface::new(8, 8).unwrap();
+        s.set_shadow(4.0, "#00ffff");
+        s.set_fill_style("#ff0000");
+        s.fill_rect(3.0, 3.0, 2.0, 2.0);
+        let rgba = s.rgba();
+        let at = |x: usize, y: usize| &rgba[(y * 8 + x) * 4..(y * 8 + x) * 4 + 4];
+        assert_eq!(at(4, 4), &[255, 0, 0, 255]);
+        assert!(at(1, 4)[3] > 0);
+        assert_eq!(at(1, 4)[0], 0);
+    }
+
+    #[test]
+    fn fill_with_a_gradient() {
+        let mut s = PixmapSurface::new(4, 4).unwrap();
+        s.set_fill_gradient(&Gradient {
+            shape: GradientShape::Linear {
+                x0: 0.0,
+                y0: 0.0,
+                x1: 0.0,
+                y1: 4.0,
+            },
+            stops: vec![(0.0, "#ff0000".into()), (1.0, "#0000ff".into())],
+        });
+        s.begin_path();
+        s.move_to(0.0, 0.0);
+        s.line_to(4.0, 0.0);
+        s.line_to(4.0, 4.0);
+        s.line_to(0.0, 4.0);
+        s.close_path();
+        s.fill();
+        let rgba = s.rgba();
+        let at = |x: usize, y: usize| &rgba[(y * 4 + x) * 4..(y * 4 + x) * 4 + 4];
+        assert!(at(1, 0)[0] > at(1, 0)[2]);
+        assert!(at(1, 3)[0] < at(1, 3)[2]);
+    }
+
+    #[test]
+    fn fill_text_draws_glyphs() {
+        let mut s = PixmapSurface::new(40, 20).unwrap();
+        s.set_fill_style("#ffffff");
+        s.set_font("16px Work Sans, sans-serif");
+        s.translate(2.0, 0.0);
+        s.fill_text("88", 0.0, 16.0);
+        let rgba = s.rgba();
+        let painted = |x0: usize, x1: usize| {
+            (0..20).any(|y| (x0..x1).any(|x| rgba[(y * 40 + x) * 4 + 3] > 0))
+        };
+        assert!(!painted(0, 2));
+        assert!(painted(2, 12));
+        assert!(painted(12, 22));
+        assert!(!painted(24, 40));
+    }
+}
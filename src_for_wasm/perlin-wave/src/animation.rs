@@ -0,0 +1,292 @@
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use wasm_bindgen::prelude::*;
+
+use perlin_wave_core::config::Config;
+use perlin_wave_core::stats::FrameRate;
+use perlin_wave_core::theme::ThemeSpec;
+use perlin_wave_core::timeline::Timeline;
+use perlin_wave_core::types::GraphType;
+
+use crate::app::App;
+use crate::error::{Error, Result};
+use crate::utils;
+
+/// Called by `request_animation_frame` with the timestamp.
+type FrameCallback = Closure<dyn FnMut(f64)>;
+
+/// Returned from `run` so that JS can control the animation.
+#[wasm_bindgen]
+pub struct Animation {
+    state: Rc<State>,
+}
+
+struct State {
+    /// `None` once stopped.
+    app: RefCell<Option<App>>,
+    timeline: RefCell<Timeline>,
+    /// Steps the timeline by a fixed amount
+    /// instead of following the timestamps.
+    fixed: bool,
+    /// Measured from the timestamps, whether `fixed` or not.
+    rate: Cell<FrameRate>,
+    running: Cell<bool>,
+    /// Handle of the pending `request_animation_frame`.
+    handle: Cell<Option<i32>>,
+    /// Holds `State` weakly so that they don't keep each other alive.
+    frame: RefCell<Option<FrameCallback>>,
+    /// What stopped the loop, until it is resumed.
+    error: RefCell<Option<JsValue>>,
+    /// Called with `error` instead of `console.error`.
+    on_error: RefCell<Option<js_sys::Function>>,
+}
+
+impl State {
+    /// Draws one frame, resetting the wave when a cycle begins.
+    /// What was changed on the sliders is applied first.
+    /// `now` is the timestamp given by `request_animation_frame`,
+    /// and it steps by the fixed amount when `None`.
+    fn tick(&self, now: Option<f64>) -> Result<()> {
+        let mut app = self
+            .app
+            .try_borrow_mut()
+            .map_err(|_| Error::Borrow("app (Animation)".into()))?;
+        let app = app
+            .as_mut()
+            .ok_or_else(|| Error::Render("Animation is stopped".into()))?;
+        let mut timeline = self
+            .timeline
+            .try_borrow_mut()
+            .map_err(|_| Error::Borrow("timeline (Animation)".into()))?;
+        if let Some(now) = now {
+            let mut rate = self.rate.get();
+            rate.record(now);
+            self.rate.set(rate);
+            app.set_frame_rate(&rate);
+        }
+        // Changes from the sliders are applied before the frame.
+        if let Some(cycle) = app.tune()? {
+            timeline.set_cycle(cycle);
+        }
+        let (frame, reset) = match now {
+            Some(now) if !self.fixed => timeline.advance(now),
+            _ => timeline.tick(),
+        };
+        if reset {
+            app.reset()?;
+        }
+        app.draw(frame)
+    }
+
+    fn request(&self) -> Result<()> {
+        let frame = self.frame.borrow();
+        let frame = frame
+            .as_ref()
+            .ok_or_else(|| Error::Render("Animation is stopped".into()))?;
+        self.handle
+            .set(Some(utils::request_animation_frame(frame)?));
+        Ok(())
+    }
+
+    fn cancel(&self) -> Result<()> {
+        if let Some(handle) = self.handle.take() {
+            utils::cancel_animation_frame(handle)?;
+        }
+        Ok(())
+    }
+
+    /// Stops the loop, and hands `err` to JS.
+    fn fail(&self, err: Error) {
+        self.running.set(false);
+        let err = JsValue::from(err);
+        *self.error.borrow_mut() = Some(err.clone());
+        // Cloned so that the callback may set another one.
+        let on_error = self.on_error.borrow().clone();
+        // Without a callback, or when it throws, to the console.
+        let reported = match on_error {
+            Some(callback) => callback.call1(&JsValue::NULL, &err).map(|_| ()),
+            None => Err(err),
+        };
+        if let Err(err) = reported {
+            web_sys::console::error_1(&err);
+        }
+    }
+}
+
+impl Animation {
+    pub fn start(config: &Config) -> Result<Animation> {
+        let state = Rc::new(State {
+            app: RefCell::new(Some(App::new(config)?)),
+            timeline: RefCell::new(match config.step {
+                Some(step) => Timeline::fixed(config.cycle, step),
+                None => Timeline::new(config.cycle),
+            }),
+            fixed: config.step.is_some(),
+            rate: Cell::new(FrameRate::default()),
+            running: Cell::new(true),
+            handle: Cell::new(None),
+            frame: RefCell::new(None),
+            error: RefCell::new(None),
+            on_error: RefCell::new(None),
+        });
+
+        let weak = Rc::downgrade(&state);
+        *state.frame.borrow_mut() = Some(Closure::wrap(Box::new(move |now: f64| {
+            let state = match weak.upgrade() {
+                Some(state) => state,
+                None => return,
+            };
+            state.handle.set(None);
+            if !state.running.get() {
+                return;
+            }
+
+            // The loop stops here by not requesting the next frame,
+            // leaving the module alive for JS to run again.
+            if let Err(err) = state.tick(Some(now)).and_then(|_| state.request()) {
+                state.fail(err);
+            }
+        }) as Box<dyn FnMut(f64)>));
+
+        state.request()?;
+
+        Ok(Animation { state })
+    }
+}
+
+/// When JS frees the animation without `stop`, the pending frame
+/// would call the closure dropped along with `State`.
+impl Drop for Animation {
+    fn drop(&mut self) {
+        if let Err(err) = self.stop() {
+            web_sys::console::error_1(&err);
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl Animation {
+    pub fn pause(&self) -> std::result::Result<(), JsValue> {
+        self.state.running.set(false);
+        if let Ok(mut timeline) = self.state.timeline.try_borrow_mut() {
+            timeline.pause();
+        }
+        let mut rate = self.state.rate.get();
+        rate.pause();
+        self.state.rate.set(rate);
+        self.state.cancel()?;
+        Ok(())
+    }
+
+    /// Does nothing when already running or stopped.
+    pub fn resume(&self) -> std::result::Result<(), JsValue> {
+        if self.state.running.get() || self.state.app.borrow().is_none() {
+            return Ok(());
+        }
+        self.state.running.set(true);
+        self.state.error.borrow_mut().take();
+        self.state.request()?;
+        Ok(())
+    }
+
+    /// Pauses, and draws the next frame
+    /// (`step` milliseconds ahead, or 1/60 seconds).
+    pub fn step(&self) -> std::result::Result<(), JsValue> {
+        self.pause()?;
+        self.state.tick(None)?;
+        Ok(())
+    }
+
+    /// Removes the canvases and the event handlers.
+    /// The animation can not be resumed afterwards.
+    /// Freeing the animation from JS stops it as well.
+    pub fn stop(&self) -> std::result::Result<(), JsValue> {
+        self.pause()?;
+        let app = self
+            .state
+            .app
+            .try_borrow_mut()
+            .map_err(|_| Error::Borrow("app (Animation::stop)".into()))?
+            .take();
+        self.state.frame.borrow_mut().take();
+        if let Some(mut app) = app {
+            app.destroy()?;
+        }
+        Ok(())
+    }
+
+    #[wasm_bindgen(js_name = "isRunning")]
+    pub fn is_running(&self) -> bool {
+        self.state.running.get()
+    }
+
+    /// The error which stopped the loop, with `kind` as `run` throws.
+    /// Cleared by `resume`.
+    #[wasm_bindgen(js_name = "lastError")]
+    pub fn last_error(&self) -> JsValue {
+        self.state.error.borrow().clone().unwrap_or(JsValue::NULL)
+    }
+
+    /// `callback` is called with the error which stopped the loop,
+    /// instead of it going to `console.error`.
+    #[wasm_bindgen(js_name = "onError")]
+    pub fn on_error(&self, callback: js_sys::Function) {
+        *self.state.on_error.borrow_mut() = Some(callback);
+    }
+
+    /// Switches the wave panel to `name` (such as `solar`).
+    /// Clicking or rotating goes on from there.
+    #[wasm_bindgen(js_name = "setGraphType")]
+    pub fn set_graph_type(&self, name: &str) -> std::result::Result<(), JsValue> {
+        let graph_type: GraphType = name.parse().map_err(Error::Argument)?;
+        let mut app = self
+            .state
+            .app
+            .try_borrow_mut()
+            .map_err(|_| Error::Borrow("app (Animation::set_graph_type)".into()))?;
+        let app = app
+            .as_mut()
+            .ok_or_else(|| Error::Render("Animation is stopped".into()))?;
+        app.set_graph_type(graph_type)?;
+        Ok(())
+    }
+
+    /// Switches every panel to `theme`, either the name of a preset
+    /// (`dark`, `light`, or `high-contrast`) or the whole theme.
+    /// `bgcolor` and the colors of each panel in the config still apply.
+    /// The canvases stay as they are.
+    #[wasm_bindgen(js_name = "setTheme")]
+    pub fn set_theme(&self, theme: &JsValue) -> std::result::Result<(), JsValue> {
+        let theme =
+            ThemeSpec::from_deserializer(serde_wasm_bindgen::Deserializer::from(theme.clone()))
+                .map_err(Error::from)?;
+        let mut app = self
+            .state
+            .app
+            .try_borrow_mut()
+            .map_err(|_| Error::Borrow("app (Animation::set_theme)".into()))?;
+        let app = app
+            .as_mut()
+            .ok_or_else(|| Error::Render("Animation is stopped".into()))?;
+        app.set_theme(&theme)?;
+        Ok(())
+    }
+
+    /// Returns the frame last drawn for the panel `id`
+    /// (either `wave` or `control`) as an SVG document.
+    #[wasm_bindgen(js_name = "exportSvg")]
+    pub fn export_svg(&self, id: &str) -> std::result::Result<String, JsValue> {
+        let app = self
+            .state
+            .app
+            .try_borrow()
+            .map_err(|_| Error::Borrow("app (Animation::export_svg)".into()))?;
+        let app = app
+            .as_ref()
+            .ok_or_else(|| Error::Render("Animation is stopped".into()))?;
+        let svg = app
+            .to_svg(id)
+            .ok_or_else(|| Error::Render(format!("No such panel: {}", id)))?;
+        Ok(svg)
+    }
+}
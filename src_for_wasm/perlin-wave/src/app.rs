@@ -1,99 +1,133 @@
-use lerp::Lerp;
-use rand::{self, Rng};
+use perlin_wave_core::config::{Config, PanelSpec};
+use perlin_wave_core::controls::{Controls, Param};
+use perlin_wave_core::stats::FrameRate;
+use perlin_wave_core::theme::Theme;
+use perlin_wave_core::timeline::Frame;
+use perlin_wave_core::types::GraphType;
+use perlin_wave_core::wave::Wave;
 
-use crate::constants::{NORMAL_WIDTH, SEGMENTS};
+use crate::error::Result;
 use crate::panels::control::ControlPanel;
 use crate::panels::wave::WavePanel;
 use crate::panels::Panel;
-use crate::perlin::noise_2d;
-use crate::types::{Config, PanelConfig, Point};
+use crate::resize::Resizer;
+use crate::utils::get_wrapper_element;
 
 pub struct App {
-    points: Vec<Point>,
-    points_prev: Vec<Point>,
+    wave: Wave,
     panels: Vec<Box<dyn Panel>>,
+    /// For the colors given to each panel when the theme changes.
+    config: Config,
+    /// The frame last drawn.
+    frame: Frame,
 }
 
 impl App {
-    pub fn new(config: &Config) -> Result<App, String> {
-        let bgcolor: String = config.bgcolor.clone();
+    pub fn new(config: &Config) -> Result<App> {
+        let theme = config.theme.theme();
 
         let mut panels = vec![];
 
         for panel in &config.panels {
-            // Casting `PanelConfig` for `Vec<HashMap<String, String>>`
-            // so that custom methods `ok` and `ok_f64` will be available.
-            let panel: &dyn PanelConfig = panel;
-
-            let id = panel.ok("id")?;
-            let width = panel.ok_f64("width")?;
-            let height = panel.ok_f64("height")?;
-            let color = panel.ok("color")?;
-            let color2 = panel.ok("color2")?;
-
-            match id.as_str() {
-                "wave" => {
-                    let pane: Box<dyn Panel> = Box::new(WavePanel::new(
-                        id.as_str(),
-                        width,
-                        height,
-                        bgcolor.as_str(),
-                        color.as_str(),
-                        color2.as_str(),
-                    )?);
-                    panels.push(pane);
-                }
-                "control" => {
-                    let pane: Box<dyn Panel> = Box::new(ControlPanel::new(
-                        id.as_str(),
-                        width,
-                        height,
-                        bgcolor.as_str(),
-                        color.as_str(),
-                        color2.as_str(),
-                    )?);
-                    panels.push(pane);
-                }
-                _ => {
-                    return Err(format!("No such panel: {}", id));
-                }
+            let id = panel.id();
+            // Pages may have only some of the panels.
+            if get_wrapper_element(id).is_err() {
+                web_sys::console::warn_1(&format!("No element for the panel: {}", id).into());
+                continue;
             }
+            let theme = config.panel_theme(id, &theme);
+            let resizer = Resizer::new(id, panel.layout())?;
+            let pane: Box<dyn Panel> = match panel {
+                PanelSpec::Wave(spec) => Box::new(WavePanel::new(id, &theme, spec, resizer)?),
+                PanelSpec::Control(spec) => Box::new(ControlPanel::new(
+                    id,
+                    &theme,
+                    spec,
+                    Controls::new(config),
+                    config.flow().cloned(),
+                    resizer,
+                )?),
+            };
+            panels.push(pane);
         }
 
         Ok(App {
-            points: vec![],
-            points_prev: vec![],
+            wave: Wave::new(config),
             panels,
+            config: config.clone(),
+            frame: Frame::default(),
         })
     }
 
-    pub fn reset(&mut self) {
-        self.points_prev = if !self.points.is_empty() {
-            self.points.clone()
-        } else {
-            vec![Point { x: 0.0, y: 0.0 }; SEGMENTS]
-        };
+    pub fn reset(&mut self) -> Result<()> {
+        self.wave.reset();
+
+        for panel in self.panels.iter_mut() {
+            panel.reset()?;
+        }
+        Ok(())
+    }
+
+    pub fn draw(&mut self, frame: Frame) -> Result<()> {
+        self.frame = frame;
+        for panel in self.panels.iter_mut() {
+            panel.fit()?;
+            panel.draw(&self.wave, frame)?;
+        }
+        Ok(())
+    }
+
+    /// Applies to the wave what the user changed on the sliders.
+    /// Returns the new length of a cycle when it was changed,
+    /// which is for the timeline.
+    pub fn tune(&mut self) -> Result<Option<f64>> {
+        let mut cycle = None;
+        for panel in self.panels.iter_mut() {
+            for (param, value) in panel.take_changes() {
+                match param {
+                    Param::Cycle => cycle = Some(value),
+                    _ => self.wave.tune(param, value),
+                }
+            }
+        }
+        Ok(cycle)
+    }
 
-        self.points = vec![Point { x: 0.0, y: 0.0 }; SEGMENTS];
+    /// For the readout of the control panels.
+    pub fn set_frame_rate(&mut self, rate: &FrameRate) {
+        for panel in self.panels.iter_mut() {
+            panel.set_frame_rate(rate);
+        }
+    }
 
-        let mut rng = rand::thread_rng();
-        let offset = rng.gen_range(0, 10) as f64;
-        for i in 0..SEGMENTS {
-            let ratio = i as f64 / SEGMENTS as f64;
-            let x: f64 = 0_f64.lerp(NORMAL_WIDTH, ratio);
-            let nx: f64 = x + offset;
-            let y: f64 = noise_2d(nx, offset);
-            self.points[i] = Point { x, y };
+    /// Changes what the wave panels show right away.
+    pub fn set_graph_type(&mut self, graph_type: GraphType) -> Result<()> {
+        for panel in self.panels.iter_mut() {
+            panel.set_graph_type(graph_type)?;
         }
+        Ok(())
+    }
 
+    /// Every panel is drawn with `theme` from the next frame,
+    /// including the colors given to each panel in `Config`.
+    pub fn set_theme(&mut self, theme: &Theme) -> Result<()> {
         for panel in self.panels.iter_mut() {
-            panel.reset();
+            panel.set_theme(&self.config.panel_theme(panel.id(), theme))?;
         }
+        Ok(())
     }
 
-    pub fn draw(&mut self, counter: u32) {
+    /// Removes the canvases and the event handlers of every panel.
+    pub fn destroy(&mut self) -> Result<()> {
         for panel in self.panels.iter_mut() {
-            panel.draw(&self.points, &self.points_prev, counter);
+            panel.destroy()?;
         }
+        Ok(())
+    }
+
+    /// The frame last drawn for the panel `id` as an SVG document.
+    pub fn to_svg(&self, id: &str) -> Option<String> {
+        let panel = self.panels.iter().find(|panel| panel.id() == id)?;
+        panel.to_svg(&self.wave, self.frame)
     }
 }
@@ -0,0 +1,190 @@
+use std::any::Any;
+
+use perlin_wave_core::surface::{Gradient, GradientShape, Surface};
+
+use crate::error::Result;
+use crate::utils::{device_pixel_ratio, get_canvas, get_ctx};
+
+/// `Surface` drawing on a 2D canvas.
+/// Renderers draw in CSS pixels, which are scaled
+/// to the pixels of the display.
+pub struct CanvasSurface {
+    canvas: web_sys::HtmlCanvasElement,
+    ctx: web_sys::CanvasRenderingContext2d,
+    /// In CSS pixels.
+    width: f64,
+    height: f64,
+    /// Pixels of the display per CSS pixel.
+    scale: f64,
+}
+
+impl CanvasSurface {
+    /// Creates a canvas and appends it to the wrapper element `id`.
+    pub fn new(id: &str, width: f64, height: f64) -> Result<CanvasSurface> {
+        let canvas = get_canvas(id)?;
+        let ctx = get_ctx(&canvas)?;
+        let mut surface = CanvasSurface {
+            canvas,
+            ctx,
+            width: 0.0,
+            height: 0.0,
+            scale: 1.0,
+        };
+        surface.resize(width, height, device_pixel_ratio());
+        Ok(surface)
+    }
+
+    /// Whether already of the size with the scale.
+    pub fn fits(&self, width: f64, height: f64, scale: f64) -> bool {
+        (self.width, self.height, self.scale) == (width, height, scale)
+    }
+
+    /// Resizes the backing store to `width` and `height`
+    /// (in CSS pixels) times `scale`, erasing what was drawn.
+    pub fn resize(&mut self, width: f64, height: f64, scale: f64) {
+        self.width = width;
+        self.height = height;
+        self.scale = scale;
+        self.canvas.set_width((width * scale).round() as u32);
+        self.canvas.set_height((height * scale).round() as u32);
+        let style = self.canvas.style();
+        style
+            .set_property("width", &format!("{}px", width))
+            .unwrap_or(());
+        style
+            .set_property("height", &format!("{}px", height))
+            .unwrap_or(());
+        // Resizing resets the context, the transform included.
+        self.ctx
+            .set_transform(scale, 0.0, 0.0, scale, 0.0, 0.0)
+            .unwrap_or(());
+    }
+
+    /// Removes the canvas from the wrapper element.
+    pub fn remove(&self) {
+        self.canvas.remove();
+    }
+
+    /// `None` when the radii are negative.
+    fn gradient(&self, gradient: &Gradient) -> Option<web_sys::CanvasGradient> {
+        let g = match gradient.shape {
+            GradientShape::Linear { x0, y0, x1, y1 } => {
+                self.ctx.create_linear_gradient(x0, y0, x1, y1)
+            }
+            GradientShape::Radial { x, y, r0, r1 } => {
+                self.ctx.create_radial_gradient(x, y, r0, x, y, r1).ok()?
+            }
+        };
+        for (offset, color) in gradient.stops.iter() {
+            g.add_color_stop(*offset as f32, color).unwrap_or(());
+        }
+        Some(g)
+    }
+}
+
+impl Surface for CanvasSurface {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn clear(&mut self) {
+        self.ctx.clear_rect(0.0, 0.0, self.width, self.height);
+    }
+
+    fn save(&mut self) {
+        self.ctx.save();
+    }
+
+    fn restore(&mut self) {
+        self.ctx.restore();
+    }
+
+    fn translate(&mut self, x: f64, y: f64) {
+        self.ctx.translate(x, y).unwrap_or(());
+    }
+
+    fn rotate(&mut self, angle: f64) {
+        self.ctx.rotate(angle).unwrap_or(());
+    }
+
+    fn set_global_alpha(&mut self, alpha: f64) {
+        self.ctx.set_global_alpha(alpha);
+    }
+
+    fn set_fill_style(&mut self, color: &str) {
+        self.ctx.set_fill_style_str(color);
+    }
+
+    fn set_fill_gradient(&mut self, gradient: &Gradient) {
+        if let Some(g) = self.gradient(gradient) {
+            self.ctx.set_fill_style_canvas_gradient(&g);
+        }
+    }
+
+    fn set_stroke_style(&mut self, color: &str) {
+        self.ctx.set_stroke_style_str(color);
+    }
+
+    fn set_stroke_gradient(&mut self, gradient: &Gradient) {
+        if let Some(g) = self.gradient(gradient) {
+            self.ctx.set_stroke_style_canvas_gradient(&g);
+        }
+    }
+
+    fn set_font(&mut self, font: &str) {
+        self.ctx.set_font(font);
+    }
+
+    fn set_line_width(&mut self, width: f64) {
+        self.ctx.set_line_width(width);
+    }
+
+    fn set_shadow(&mut self, blur: f64, color: &str) {
+        self.ctx.set_shadow_blur(blur);
+        self.ctx.set_shadow_color(color);
+    }
+
+    fn begin_path(&mut self) {
+        self.ctx.begin_path();
+    }
+
+    fn move_to(&mut self, x: f64, y: f64) {
+        self.ctx.move_to(x, y);
+    }
+
+    fn line_to(&mut self, x: f64, y: f64) {
+        self.ctx.line_to(x, y);
+    }
+
+    fn bezier_curve_to(&mut self, c1x: f64, c1y: f64, c2x: f64, c2y: f64, x: f64, y: f64) {
+        self.ctx.bezier_curve_to(c1x, c1y, c2x, c2y, x, y);
+    }
+
+    fn arc(&mut self, x: f64, y: f64, radius: f64, start: f64, end: f64) {
+        self.ctx.arc(x, y, radius, start, end).unwrap_or(());
+    }
+
+    fn close_path(&mut self) {
+        self.ctx.close_path();
+    }
+
+    fn stroke(&mut self) {
+        self.ctx.stroke();
+    }
+
+    fn fill(&mut self) {
+        self.ctx.fill();
+    }
+
+    fn fill_rect(&mut self, x: f64, y: f64, width: f64, height: f64) {
+        self.ctx.fill_rect(x, y, width, height);
+    }
+
+    fn fill_text(&mut self, text: &str, x: f64, y: f64) {
+        self.ctx.fill_text(text, x, y).unwrap_or(());
+    }
+}
@@ -0,0 +1,86 @@
+use std::fmt;
+use wasm_bindgen::JsValue;
+
+use perlin_wave_core::config::ConfigError;
+
+/// Everything that can go wrong in the web front-end.
+/// At the wasm boundary, it becomes a JS `Error`
+/// carrying `kind` so that JS can tell them apart.
+#[derive(Debug)]
+pub enum Error {
+    /// `Config` given to `run` was invalid.
+    Config(ConfigError),
+    /// Missing window, document, or elements.
+    Dom(String),
+    /// Failed to get 2D context of a canvas.
+    Context(String),
+    /// Something was already borrowed elsewhere.
+    Borrow(String),
+    /// Failed while drawing a frame.
+    Render(String),
+    /// Invalid argument given to a method of `Animation`.
+    Argument(String),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl Error {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Error::Config(_) => "config",
+            Error::Dom(_) => "dom",
+            Error::Context(_) => "context",
+            Error::Borrow(_) => "borrow",
+            Error::Render(_) => "render",
+            Error::Argument(_) => "argument",
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Config(err) => write!(f, "{}", err),
+            Error::Dom(message) => write!(f, "DOM: {}", message),
+            Error::Context(message) => write!(f, "Canvas context: {}", message),
+            Error::Borrow(what) => write!(f, "Failed to borrow: {}", what),
+            Error::Render(message) => write!(f, "Render: {}", message),
+            Error::Argument(message) => write!(f, "Argument: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<ConfigError> for Error {
+    fn from(err: ConfigError) -> Error {
+        Error::Config(err)
+    }
+}
+
+impl From<Error> for JsValue {
+    /// `Error` of JS with `kind`, and for `config`,
+    /// `errors` listing `{ path, message }` of each field.
+    fn from(err: Error) -> JsValue {
+        let js_err = js_sys::Error::new(&err.to_string());
+        let set = |target: &JsValue, key: &str, value: &JsValue| {
+            // Only fails when `target` is not an object.
+            js_sys::Reflect::set(target, &key.into(), value).unwrap_or(false);
+        };
+
+        set(&js_err, "kind", &err.kind().into());
+
+        if let Error::Config(config_err) = &err {
+            let errors = js_sys::Array::new();
+            for field in &config_err.errors {
+                let obj: JsValue = js_sys::Object::new().into();
+                set(&obj, "path", &field.path.as_str().into());
+                set(&obj, "message", &field.message.as_str().into());
+                errors.push(&obj);
+            }
+            set(&js_err, "errors", &errors);
+        }
+
+        js_err.into()
+    }
+}
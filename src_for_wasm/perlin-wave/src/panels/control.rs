@@ -1,55 +1,231 @@
 use core::cell::RefCell;
 use std::rc::Rc;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::{HtmlElement, PointerEvent};
 
-use crate::exit;
-use crate::graphics::control::ControlGraphics;
-use crate::graphics::Graphics;
+use perlin_wave_core::config::ControlPanelSpec;
+use perlin_wave_core::controls::{Controls, Param};
+use perlin_wave_core::graphics::control::ControlGraphics;
+use perlin_wave_core::graphics::Graphics;
+use perlin_wave_core::stats::{FrameRate, Readout};
+use perlin_wave_core::svg;
+use perlin_wave_core::theme::Theme;
+use perlin_wave_core::timeline::Frame;
+use perlin_wave_core::types::Point;
+use perlin_wave_core::wave::{FlowConfig, Wave};
+
+use crate::canvas::CanvasSurface;
+use crate::error::{Error, Result};
 use crate::panels::Panel;
-use crate::types::Point;
+use crate::panels::{remove_canvas, reset_graphics};
+use crate::resize::Resizer;
+use crate::utils::get_wrapper_element;
+
+type PointerHandler = Closure<dyn FnMut(PointerEvent)>;
 
-#[derive(Clone)]
 pub struct ControlPanel {
     id: String,
+    el: HtmlElement,
     g: Rc<RefCell<dyn Graphics>>,
+    resizer: Resizer,
+    /// Of the animation loop, for the readout.
+    rate: FrameRate,
+    /// A cycle began, and its value is not in the history yet.
+    cycled: bool,
+    /// Of the wave panel in `WaveMode::Flow`, whose points
+    /// are described instead of those of the cycle.
+    flow: Option<FlowConfig>,
+    /// Kept for the pointer events of `el` until `destroy`.
+    handlers: Vec<PointerHandler>,
+}
+
+/// Runs `f` with the sliders of `g` and its size, if any.
+fn with_controls<F>(g: &RefCell<dyn Graphics>, f: F)
+where
+    F: FnOnce(&mut Controls, f64, f64),
+{
+    let mut g = match g.try_borrow_mut() {
+        Ok(g) => g,
+        Err(_) => return,
+    };
+    if let Some(g) = g.as_any_mut().downcast_mut::<ControlGraphics>() {
+        let (width, height) = (g.width, g.height);
+        if let Some(controls) = g.controls_mut() {
+            f(controls, width, height);
+        }
+    }
+}
+
+/// Where the pointer is in `el`, in CSS pixels.
+fn offset(el: &HtmlElement, e: &PointerEvent) -> (f64, f64) {
+    let rect = el.get_bounding_client_rect();
+    (
+        e.client_x() as f64 - rect.left(),
+        e.client_y() as f64 - rect.top(),
+    )
 }
 
 impl Panel for ControlPanel {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
     fn g(&self) -> Rc<RefCell<dyn Graphics>> {
         self.g.clone()
     }
 
-    fn draw(&mut self, points: &[Point], _points_prev: &[Point], _counter: u32) {
-        if let Ok(mut g) = self.g.try_borrow_mut() {
-            if let Some(g) = g.as_any_mut().downcast_mut::<ControlGraphics>() {
-                g.clear();
-                g.render_control(&points);
-            } else {
-                exit("Faile to downcast_mut::<ControlGraphics>()");
-            }
-        } else {
-            exit("Faile to borrow: self.g (ControlPanel::draw)");
+    fn resizer(&self) -> &Resizer {
+        &self.resizer
+    }
+
+    fn reset(&mut self) -> Result<()> {
+        self.cycled = true;
+        reset_graphics(&self.g, &self.id)
+    }
+
+    fn draw(&mut self, wave: &Wave, frame: Frame) -> Result<()> {
+        let points = self.points(wave, frame);
+        let mut g = self
+            .g
+            .try_borrow_mut()
+            .map_err(|_| Error::Borrow(format!("self.g (ControlPanel::draw) {}", self.id)))?;
+        let g = g
+            .as_any_mut()
+            .downcast_mut::<ControlGraphics>()
+            .ok_or_else(|| Error::Render("Failed to downcast_mut::<ControlGraphics>()".into()))?;
+        if self.cycled {
+            g.push_history(&points);
+            self.cycled = false;
         }
+        g.clear();
+        g.render_control(&points, &self.readout(wave));
+        Ok(())
+    }
+
+    fn to_svg(&self, wave: &Wave, frame: Frame) -> Option<String> {
+        let points = self.points(wave, frame);
+        let g = self.g.try_borrow().ok()?;
+        let g = g.as_any().downcast_ref::<ControlGraphics>()?;
+        Some(svg::control_to_svg(g, &points, &self.readout(wave)))
+    }
+
+    fn set_frame_rate(&mut self, rate: &FrameRate) {
+        self.rate = *rate;
+    }
+
+    fn take_changes(&mut self) -> Vec<(Param, f64)> {
+        let mut changes = vec![];
+        with_controls(&self.g, |controls, _, _| {
+            changes = controls.take_changes();
+        });
+        changes
+    }
+
+    fn destroy(&mut self) -> Result<()> {
+        self.el.set_onpointerdown(None);
+        self.el.set_onpointermove(None);
+        self.el.set_onpointerup(None);
+        self.el.set_onpointercancel(None);
+        self.handlers.clear();
+        self.resizer.disconnect();
+        remove_canvas(&self.g, &self.id)
     }
 }
 
 impl ControlPanel {
+    /// The same points as the wave panel draws.
+    fn points(&self, wave: &Wave, frame: Frame) -> Vec<Point> {
+        match &self.flow {
+            Some(flow) => wave.flow(frame.time, flow),
+            None => wave.points().to_vec(),
+        }
+    }
+
+    fn readout(&self, wave: &Wave) -> Readout {
+        Readout {
+            seed: wave.seed(),
+            noise: wave.algorithm(),
+            rate: self.rate,
+        }
+    }
+
+    /// Sized by `resizer` which measured the wrapper element.
+    /// Shows the metrics and the sparkline of `spec`,
+    /// and the sliders of `controls` dragged with the pointer.
+    /// With `flow`, of the points the wave panel draws in `WaveMode::Flow`.
     pub fn new(
         id: &str,
-        width: f64,
-        height: f64,
-        bgcolor: &str,
-        color: &str,
-        _color2: &str,
-    ) -> Result<ControlPanel, String> {
+        theme: &Theme,
+        spec: &ControlPanelSpec,
+        controls: Controls,
+        flow: Option<FlowConfig>,
+        resizer: Resizer,
+    ) -> Result<ControlPanel> {
+        let (width, height) = (resizer.width, resizer.height);
+        let el: HtmlElement = get_wrapper_element(id)?;
         web_sys::console::log_1(
             &(format!("(control) {} x {}", width as u32, height as u32).into()),
         );
 
-        let g = ControlGraphics::new(id, width, height, bgcolor, color)?;
+        let surface = CanvasSurface::new(id, width, height)?;
+        let mut g = ControlGraphics::new(
+            Box::new(surface),
+            width,
+            height,
+            &theme.background,
+            &theme.secondary,
+        );
+        g.set_theme(theme);
+        g.set_readout(spec.metrics.clone(), spec.readout);
+        g.set_history(spec.history);
+        g.set_controls(controls);
+        let g: Rc<RefCell<dyn Graphics>> = Rc::new(RefCell::new(g));
+
+        // Otherwise, touches scroll the page instead of dragging.
+        el.style()
+            .set_property("touch-action", "none")
+            .unwrap_or(());
+
+        let (g_clone, el_clone) = (g.clone(), el.clone());
+        let down = Closure::wrap(Box::new(move |e: PointerEvent| {
+            let (x, y) = offset(&el_clone, &e);
+            with_controls(&g_clone, |controls, width, height| {
+                if controls.pointer_down(x, y, width, height) {
+                    // Keeps the events coming while dragged out of `el`.
+                    el_clone.set_pointer_capture(e.pointer_id()).unwrap_or(());
+                    e.prevent_default();
+                }
+            });
+        }) as Box<dyn FnMut(PointerEvent)>);
+
+        let (g_clone, el_clone) = (g.clone(), el.clone());
+        let drag = Closure::wrap(Box::new(move |e: PointerEvent| {
+            let (x, _) = offset(&el_clone, &e);
+            with_controls(&g_clone, |controls, width, height| {
+                controls.pointer_move(x, width, height);
+            });
+        }) as Box<dyn FnMut(PointerEvent)>);
+
+        let g_clone = g.clone();
+        let up = Closure::wrap(Box::new(move |_: PointerEvent| {
+            with_controls(&g_clone, |controls, _, _| controls.pointer_up());
+        }) as Box<dyn FnMut(PointerEvent)>);
+
+        el.set_onpointerdown(Some(down.as_ref().unchecked_ref()));
+        el.set_onpointermove(Some(drag.as_ref().unchecked_ref()));
+        el.set_onpointerup(Some(up.as_ref().unchecked_ref()));
+        el.set_onpointercancel(Some(up.as_ref().unchecked_ref()));
 
         Ok(ControlPanel {
             id: id.into(),
-            g: Rc::new(RefCell::new(g)),
+            el,
+            g,
+            resizer,
+            rate: FrameRate::default(),
+            cycled: false,
+            flow,
+            handlers: vec![down, drag, up],
         })
     }
 }
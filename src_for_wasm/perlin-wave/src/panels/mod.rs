@@ -4,18 +4,107 @@ pub mod wave;
 use core::cell::RefCell;
 use std::rc::Rc;
 
-use crate::graphics::Graphics;
-use crate::types::Point;
+use perlin_wave_core::controls::Param;
+use perlin_wave_core::graphics::Graphics;
+use perlin_wave_core::stats::FrameRate;
+use perlin_wave_core::theme::Theme;
+use perlin_wave_core::timeline::Frame;
+use perlin_wave_core::types::GraphType;
+use perlin_wave_core::wave::Wave;
+
+use crate::canvas::CanvasSurface;
+use crate::error::{Error, Result};
+use crate::resize::Resizer;
+use crate::utils::device_pixel_ratio;
 
 pub trait Panel {
+    fn id(&self) -> &str;
+
     fn g(&self) -> Rc<RefCell<dyn Graphics>>;
 
-    fn reset(&mut self) {
-        if let Ok(mut g) = self.g().try_borrow_mut() {
-            let (width, height) = g.size();
-            g.reset(width, height);
-        };
+    fn resizer(&self) -> &Resizer;
+
+    /// Fits the canvas to the wrapper element when it was resized,
+    /// or moved to a display of another pixel ratio.
+    /// Called before every `draw`.
+    fn fit(&mut self) -> Result<()> {
+        fit_canvas(&self.g(), self.id(), self.resizer())
+    }
+
+    /// Called whenever a cycle begins.
+    fn reset(&mut self) -> Result<()> {
+        reset_graphics(&self.g(), self.id())
+    }
+
+    fn draw(&mut self, wave: &Wave, frame: Frame) -> Result<()>;
+
+    /// The same frame as `draw`, but as an SVG document.
+    fn to_svg(&self, wave: &Wave, frame: Frame) -> Option<String>;
+
+    /// Does nothing for panels without graph types.
+    fn set_graph_type(&mut self, _graph_type: GraphType) -> Result<()> {
+        Ok(())
     }
 
-    fn draw(&mut self, points: &[Point], points_prev: &[Point], counter: u32);
+    /// Drawn with `theme` from the next frame.
+    fn set_theme(&mut self, theme: &Theme) -> Result<()> {
+        self.g()
+            .try_borrow_mut()
+            .map_err(|_| Error::Borrow(format!("g (Panel::set_theme) {}", self.id())))?
+            .set_theme(theme);
+        Ok(())
+    }
+
+    /// Does nothing for panels without the readout.
+    fn set_frame_rate(&mut self, _rate: &FrameRate) {}
+
+    /// The parameters changed by the user since the last time.
+    /// Always empty for panels without sliders.
+    fn take_changes(&mut self) -> Vec<(Param, f64)> {
+        vec![]
+    }
+
+    /// Removes whatever was added to the DOM.
+    fn destroy(&mut self) -> Result<()> {
+        self.resizer().disconnect();
+        remove_canvas(&self.g(), self.id())
+    }
+}
+
+pub fn reset_graphics(g: &RefCell<dyn Graphics>, id: &str) -> Result<()> {
+    let mut g = g
+        .try_borrow_mut()
+        .map_err(|_| Error::Borrow(format!("g (reset_graphics) {}", id)))?;
+    let (width, height) = g.size();
+    g.reset(width, height);
+    Ok(())
+}
+
+pub fn fit_canvas(g: &RefCell<dyn Graphics>, id: &str, resizer: &Resizer) -> Result<()> {
+    let mut g = g
+        .try_borrow_mut()
+        .map_err(|_| Error::Borrow(format!("g (fit_canvas) {}", id)))?;
+    let (width, height) = resizer.take().unwrap_or_else(|| g.size());
+    let scale = device_pixel_ratio();
+    let surface = match g.surface().as_any_mut().downcast_mut::<CanvasSurface>() {
+        Some(surface) => surface,
+        None => return Ok(()),
+    };
+    if surface.fits(width, height, scale) {
+        return Ok(());
+    }
+    surface.resize(width, height, scale);
+    // For `SolarInfo` and the font to follow the size.
+    g.reset(width, height);
+    Ok(())
+}
+
+pub fn remove_canvas(g: &RefCell<dyn Graphics>, id: &str) -> Result<()> {
+    let mut g = g
+        .try_borrow_mut()
+        .map_err(|_| Error::Borrow(format!("g (remove_canvas) {}", id)))?;
+    if let Some(surface) = g.surface().as_any_mut().downcast_mut::<CanvasSurface>() {
+        surface.remove();
+    }
+    Ok(())
 }
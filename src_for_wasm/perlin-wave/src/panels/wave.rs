@@ -1,95 +1,189 @@
 use core::cell::RefCell;
-use std::cell::Cell;
 use std::rc::Rc;
 use wasm_bindgen::prelude::*;
 use wasm_bindgen::JsCast;
 use web_sys::HtmlElement;
 
-use crate::exit;
-use crate::graphics::wave::WaveGraphics;
-use crate::graphics::Graphics;
-use crate::panels::wave::GraphType::{Bars, Radio, Solar};
-use crate::panels::Panel;
-use crate::types::Point;
+use perlin_wave_core::config::WavePanelSpec;
+use perlin_wave_core::graphics::wave::WaveGraphics;
+use perlin_wave_core::graphics::Graphics;
+use perlin_wave_core::rotation::Rotation;
+use perlin_wave_core::svg;
+use perlin_wave_core::theme::Theme;
+use perlin_wave_core::timeline::Frame;
+use perlin_wave_core::transition::Transition;
+use perlin_wave_core::types::{GraphType, Point};
+use perlin_wave_core::wave::{FlowConfig, Wave, WaveMode};
+
+use crate::canvas::CanvasSurface;
+use crate::error::{Error, Result};
+use crate::panels::{remove_canvas, reset_graphics, Panel};
+use crate::resize::Resizer;
 use crate::utils::get_wrapper_element;
 
-#[derive(Clone)]
 pub struct WavePanel {
     id: String,
+    el: HtmlElement,
     g: Rc<RefCell<dyn Graphics>>,
-    graph_type: Rc<Cell<GraphType>>,
+    resizer: Resizer,
+    /// Shared with `onclick`.
+    rotation: Rc<RefCell<Rotation>>,
+    transition: Transition,
+    /// `Some` for `WaveMode::Flow`.
+    flow: Option<FlowConfig>,
+    /// Kept for `onclick` of `el` until `destroy`.
+    onclick: Option<Closure<dyn FnMut()>>,
 }
 
 impl Panel for WavePanel {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
     fn g(&self) -> Rc<RefCell<dyn Graphics>> {
         self.g.clone()
     }
 
-    fn draw(&mut self, points: &[Point], points_prev: &[Point], counter: u32) {
-        if let Ok(mut g) = self.g.try_borrow_mut() {
-            if let Some(g) = g.as_any_mut().downcast_mut::<WaveGraphics>() {
-                g.clear();
-                match self.graph_type.get() {
-                    GraphType::Radio => g.render_radio(points, counter),
-                    GraphType::Bars => g.render_bars(points, points_prev, counter),
-                    GraphType::Solar => g.render_solar(points, points_prev, counter),
-                }
-            } else {
-                exit("Failed to downcast_mut::<WaveGraphics>()");
-            }
-        } else {
-            exit("Failed to borrow: self.g (WavePanel::draw)");
+    fn resizer(&self) -> &Resizer {
+        &self.resizer
+    }
+
+    fn reset(&mut self) -> Result<()> {
+        reset_graphics(&self.g, &self.id)?;
+        self.rotation
+            .try_borrow_mut()
+            .map_err(|_| Error::Borrow(format!("self.rotation (WavePanel::reset) {}", self.id)))?
+            .cycle();
+        Ok(())
+    }
+
+    fn draw(&mut self, wave: &Wave, frame: Frame) -> Result<()> {
+        let blend = self.transition.update(self.graph_type()?, frame.time);
+        let (points, frame) = self.points(wave, frame);
+        let mut g = self
+            .g
+            .try_borrow_mut()
+            .map_err(|_| Error::Borrow(format!("self.g (WavePanel::draw) {}", self.id)))?;
+        let g = g
+            .as_any_mut()
+            .downcast_mut::<WaveGraphics>()
+            .ok_or_else(|| Error::Render("Failed to downcast_mut::<WaveGraphics>()".into()))?;
+        g.clear();
+        match &points {
+            Some(points) => g.render_blend(blend, points, points, frame),
+            None => g.render_blend(blend, wave.points(), wave.points_prev(), frame),
         }
+        Ok(())
+    }
+
+    fn to_svg(&self, wave: &Wave, frame: Frame) -> Option<String> {
+        let graph_type = self.graph_type().ok()?;
+        let (points, frame) = self.points(wave, frame);
+        let g = self.g.try_borrow().ok()?;
+        let g = g.as_any().downcast_ref::<WaveGraphics>()?;
+        Some(svg::wave_to_svg(
+            g,
+            graph_type,
+            points.as_deref().unwrap_or_else(|| wave.points()),
+            points.as_deref().unwrap_or_else(|| wave.points_prev()),
+            frame,
+        ))
+    }
+
+    fn set_graph_type(&mut self, graph_type: GraphType) -> Result<()> {
+        self.rotation
+            .try_borrow_mut()
+            .map_err(|_| {
+                Error::Borrow(format!(
+                    "self.rotation (WavePanel::set_graph_type) {}",
+                    self.id
+                ))
+            })?
+            .set(graph_type);
+        Ok(())
+    }
+
+    fn destroy(&mut self) -> Result<()> {
+        self.el.set_onclick(None);
+        self.onclick.take();
+        self.resizer.disconnect();
+        remove_canvas(&self.g, &self.id)
     }
 }
 
 impl WavePanel {
+    fn graph_type(&self) -> Result<GraphType> {
+        let rotation = self
+            .rotation
+            .try_borrow()
+            .map_err(|_| Error::Borrow(format!("self.rotation (WavePanel) {}", self.id)))?;
+        Ok(rotation.current())
+    }
+
+    /// For `WaveMode::Flow`, the points sampled at the time,
+    /// and the frame for which they are drawn at the full height.
+    /// Otherwise, `None` for the points of the cycle.
+    fn points(&self, wave: &Wave, frame: Frame) -> (Option<Vec<Point>>, Frame) {
+        match &self.flow {
+            Some(flow) => (
+                Some(wave.flow(frame.time, flow)),
+                Frame::middle(frame.cycle),
+            ),
+            None => (None, frame),
+        }
+    }
+
+    /// Sized by `resizer` which measured the wrapper element.
     pub fn new(
         id: &str,
-        width: f64,
-        height: f64,
-        bgcolor: &str,
-        _color: &str,
-        color2: &str,
-    ) -> Result<WavePanel, String> {
+        theme: &Theme,
+        spec: &WavePanelSpec,
+        resizer: Resizer,
+    ) -> Result<WavePanel> {
+        let (width, height) = (resizer.width, resizer.height);
         let el: HtmlElement = get_wrapper_element(id)?;
         web_sys::console::log_1(&(format!("(wave) {} x {}", width as u32, height as u32).into()));
 
-        let g = WaveGraphics::new(id, width, height, bgcolor, color2)?;
-        let graph_type: Rc<Cell<GraphType>> = Rc::new(Cell::new(GraphType::Radio));
+        let surface = CanvasSurface::new(id, width, height)?;
+        let mut g = WaveGraphics::new(
+            Box::new(surface),
+            width,
+            height,
+            &theme.background,
+            &theme.primary,
+        );
+        g.set_theme(theme);
+        g.set_easing(spec.easing.clone());
+        g.set_curve(spec.curve.clone());
+        g.set_colors(spec.colors.clone());
+        g.set_peak_color(spec.color.clone());
+        let rotation = Rc::new(RefCell::new(Rotation::new(
+            spec.graph,
+            &spec.rotation,
+            spec.rotate_every,
+        )));
 
-        let graph_type_clone = graph_type.clone();
+        let rotation_clone = rotation.clone();
         let f = Closure::wrap(Box::new(move || {
-            graph_type_clone.set(graph_type_clone.get().toggle());
+            if let Ok(mut rotation) = rotation_clone.try_borrow_mut() {
+                rotation.toggle();
+            }
         }) as Box<dyn FnMut()>);
 
         el.set_onclick(Some(f.as_ref().unchecked_ref()));
-        f.forget();
 
         Ok(WavePanel {
             id: id.into(),
+            el,
             g: Rc::new(RefCell::new(g)),
-            graph_type,
+            resizer,
+            rotation,
+            transition: Transition::new(spec.transition.clone(), spec.graph),
+            flow: match spec.mode {
+                WaveMode::Pulse => None,
+                WaveMode::Flow => Some(spec.flow.clone()),
+            },
+            onclick: Some(f),
         })
     }
 }
-
-#[derive(Copy, Clone, Debug)]
-pub enum GraphType {
-    Radio,
-    Bars,
-    Solar,
-}
-
-impl GraphType {
-    pub fn toggle(&self) -> Self {
-        web_sys::console::log_1(&(format!("toggle[0]: {:?}", self).into()));
-        let res = match self {
-            Radio => Bars,
-            Bars => Solar,
-            Solar => Radio,
-        };
-        web_sys::console::log_1(&(format!("toggle[1]: {:?}", res).into()));
-        res
-    }
-}
@@ -0,0 +1,109 @@
+//! Follows the size of the wrapper element of a panel.
+
+use std::cell::Cell;
+use std::rc::Rc;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::{CssStyleDeclaration, HtmlElement, ResizeObserver, ResizeObserverEntry};
+
+use perlin_wave_core::layout::{Layout, Style, Wrapper};
+
+use crate::error::{Error, Result};
+use crate::utils::get_wrapper_element;
+
+/// Measures the wrapper element for the size of the panel,
+/// and observes it for the size whenever it is resized.
+pub struct Resizer {
+    /// In CSS pixels, when first measured.
+    pub width: f64,
+    pub height: f64,
+    el: HtmlElement,
+    /// Restores the inline styles of `el` on `disconnect`.
+    wrapper: Wrapper,
+    observer: ResizeObserver,
+    /// Kept for `observer` until `disconnect`.
+    _callback: Closure<dyn FnMut(js_sys::Array)>,
+    /// The size observed but not taken yet.
+    pending: Rc<Cell<Option<(f64, f64)>>>,
+}
+
+/// Inline styles of the wrapper element.
+struct InlineStyle(CssStyleDeclaration);
+
+impl Style for InlineStyle {
+    fn get(&self, name: &str) -> String {
+        self.0.get_property_value(name).unwrap_or_default()
+    }
+
+    fn set(&mut self, name: &str, value: &str) {
+        if value.is_empty() {
+            self.0.remove_property(name).map(|_| ()).unwrap_or(());
+        } else {
+            self.0.set_property(name, value).unwrap_or(());
+        }
+    }
+}
+
+impl Resizer {
+    pub fn new(id: &str, layout: Layout) -> Result<Resizer> {
+        let el: HtmlElement = get_wrapper_element(id)?;
+        let wrapper = Wrapper::new(
+            &mut InlineStyle(el.style()),
+            layout,
+            el.client_height() as f64,
+        );
+        let (width, height) = wrapper.fit(
+            &mut InlineStyle(el.style()),
+            el.client_width() as f64,
+            el.client_height() as f64,
+        );
+
+        let pending = Rc::new(Cell::new(None));
+        let last = Cell::new((0.0, 0.0));
+        let pending_clone = pending.clone();
+        let el_clone = el.clone();
+        let wrapper_clone = wrapper.clone();
+        let auto = wrapper.auto();
+        let callback = Closure::wrap(Box::new(move |entries: js_sys::Array| {
+            let entry = match entries.get(0).dyn_into::<ResizeObserverEntry>() {
+                Ok(entry) => entry,
+                Err(_) => return,
+            };
+            let rect = entry.content_rect();
+            // Setting the height in `fit` is observed as well.
+            let observed = (rect.width(), if auto { 0.0 } else { rect.height() });
+            if observed.0 <= 0.0 || observed == last.get() {
+                return;
+            }
+            last.set(observed);
+            let size =
+                wrapper_clone.fit(&mut InlineStyle(el_clone.style()), observed.0, observed.1);
+            pending_clone.set(Some(size));
+        }) as Box<dyn FnMut(js_sys::Array)>);
+
+        let observer = ResizeObserver::new(callback.as_ref().unchecked_ref())
+            .map_err(|_| Error::Dom("Failed to create ResizeObserver".into()))?;
+        observer.observe(&el);
+
+        Ok(Resizer {
+            width,
+            height,
+            el,
+            wrapper,
+            observer,
+            _callback: callback,
+            pending,
+        })
+    }
+
+    /// The size in CSS pixels, when resized since the last time.
+    pub fn take(&self) -> Option<(f64, f64)> {
+        self.pending.take()
+    }
+
+    /// Stops observing, and gives the wrapper back its inline styles.
+    pub fn disconnect(&self) {
+        self.observer.disconnect();
+        self.wrapper.restore(&mut InlineStyle(self.el.style()));
+    }
+}
@@ -1,72 +1,74 @@
 use wasm_bindgen::prelude::*;
 use wasm_bindgen::JsCast;
+
+use crate::error::{Error, Result};
 // use rand_os::rand_core::RngCore;
 // use rand_os::OsRng;
 
-fn window() -> Result<web_sys::Window, String> {
-    web_sys::window().ok_or_else(|| "No window".into())
+fn window() -> Result<web_sys::Window> {
+    web_sys::window().ok_or_else(|| Error::Dom("No window".into()))
 }
 
-fn document() -> Result<web_sys::Document, String> {
-    window()?.document().ok_or_else(|| "No document".into())
+fn document() -> Result<web_sys::Document> {
+    window()?
+        .document()
+        .ok_or_else(|| Error::Dom("No document".into()))
 }
 
-pub fn request_animation_frame(f: &Closure<dyn FnMut()>) {
-    window()
-        .unwrap()
+/// Returns the handle for `cancel_animation_frame`.
+pub fn request_animation_frame(f: &Closure<dyn FnMut(f64)>) -> Result<i32> {
+    window()?
         .request_animation_frame(f.as_ref().unchecked_ref())
-        .expect("Failed to start request_animation_frame");
+        .map_err(|_| Error::Dom("Failed to start request_animation_frame".into()))
+}
+
+pub fn cancel_animation_frame(handle: i32) -> Result<()> {
+    window()?
+        .cancel_animation_frame(handle)
+        .map_err(|_| Error::Dom("Failed to cancel_animation_frame".into()))
 }
 
-pub fn get_wrapper_element(name: &str) -> Result<web_sys::HtmlElement, String> {
+pub fn get_wrapper_element(name: &str) -> Result<web_sys::HtmlElement> {
     let elem: web_sys::Element = document()?
         .get_element_by_id(name)
-        .ok_or(format!("No element: {}", name))?;
+        .ok_or_else(|| Error::Dom(format!("No element: {}", name)))?;
 
-    Ok(elem
-        .dyn_into::<web_sys::HtmlElement>()
-        .map_err(|e| e.to_string())?)
+    elem.dyn_into::<web_sys::HtmlElement>()
+        .map_err(|_| Error::Dom(format!("Not an HTML element: {}", name)))
 }
 
-pub fn create_canvas() -> Result<web_sys::HtmlCanvasElement, String> {
-    let canvas = document()?
+pub fn create_canvas() -> Result<web_sys::HtmlCanvasElement> {
+    document()?
         .create_element("canvas")
-        .map_err(|_| "Failed to create canvas".to_string())?
+        .map_err(|_| Error::Dom("Failed to create canvas".into()))?
         .dyn_into::<web_sys::HtmlCanvasElement>()
-        .map_err(|e| e.to_string())?;
-    Ok(canvas)
+        .map_err(|_| Error::Dom("Not a canvas".into()))
 }
 
-pub fn get_canvas(id: &str, width: f64, height: f64) -> Result<web_sys::HtmlCanvasElement, String> {
+/// Backing store pixels per CSS pixel, which is `2.0` on retina displays.
+pub fn device_pixel_ratio() -> f64 {
+    window().map_or(1.0, |w| w.device_pixel_ratio())
+}
+
+/// Appends a canvas to the wrapper element `id`.
+/// Its size is set later by `CanvasSurface::resize`.
+pub fn get_canvas(id: &str) -> Result<web_sys::HtmlCanvasElement> {
     let canvas = create_canvas()?;
-    canvas.set_width(width as u32);
-    canvas.set_height(height as u32);
 
     get_wrapper_element(id)?
         .append_child(&canvas)
-        .map_err(|_| "Failed append canvas".to_string())?;
+        .map_err(|_| Error::Dom(format!("Failed to append canvas: {}", id)))?;
 
     Ok(canvas)
 }
 
-pub fn get_ctx(
-    canvas: &web_sys::HtmlCanvasElement,
-) -> Result<web_sys::CanvasRenderingContext2d, String> {
-    let ctx = canvas
+pub fn get_ctx(canvas: &web_sys::HtmlCanvasElement) -> Result<web_sys::CanvasRenderingContext2d> {
+    canvas
         .get_context("2d")
-        .unwrap()
-        .unwrap()
+        .map_err(|_| Error::Context("Failed to get_context(\"2d\")".into()))?
+        .ok_or_else(|| Error::Context("No 2d context".into()))?
         .dyn_into::<web_sys::CanvasRenderingContext2d>()
-        .unwrap();
-    Ok(ctx)
-}
-
-pub fn ease_in_out_quad(v: f64) -> f64 {
-    if v < 0.5 {
-        v * v * 2.0
-    } else {
-        v * (4.0 - v * 2.0) - 1.0
-    }
+        .map_err(|_| Error::Context("Not a CanvasRenderingContext2d".into()))
 }
 
 // pub fn osrng_gen() -> f64 {